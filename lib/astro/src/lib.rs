@@ -0,0 +1,28 @@
+#![warn(clippy::pedantic)]
+#![warn(clippy::all, rust_2018_idioms)]
+
+//! Offline solar and lunar calculations from a location and time.
+//!
+//! Based on the algorithms in Jean Meeus, "Astronomical Algorithms" (2nd ed.),
+//! simplified to roughly minute-level accuracy, which is plenty for display.
+//!
+//! All times are unix timestamps in seconds, and all angles are in degrees.
+
+mod moon;
+mod sun;
+mod time;
+
+pub use moon::{
+    illumination, moon_phase, moon_position, moon_times, next_lunation, phase_name, Lunation,
+    MoonTimes, SYNODIC_MONTH,
+};
+pub use sun::{sun_position, sun_times, SunTimes};
+
+/// Position of a body in the sky, as seen from a location on Earth.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    /// Degrees above the horizon, negative when below it.
+    pub altitude: f64,
+    /// Degrees clockwise from north.
+    pub azimuth: f64,
+}
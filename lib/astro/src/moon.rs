@@ -0,0 +1,257 @@
+use std::f64::consts::PI;
+
+use super::sun;
+use super::time::{days, equatorial, horizontal, julian_day, unix_time, SECONDS_PER_DAY};
+use super::time::{DELTA_T, J2000, RAD};
+use super::Position;
+
+/// Mean length of a lunation, in days.
+pub const SYNODIC_MONTH: f64 = 29.530_588_861;
+
+/// Moonrise and moonset within a day. The moon doesn't rise or set on
+/// roughly one day each month.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MoonTimes {
+    pub rise: Option<i64>,
+    pub set: Option<i64>,
+}
+
+// Ecliptic longitude, latitude (radians) and distance (km) of the moon,
+// keeping only the largest periodic terms from Meeus ch. 47.
+fn coordinates(d: f64) -> (f64, f64, f64) {
+    let l = (218.316 + 13.176_396 * d) * RAD;
+    // moon's mean anomaly, mean elongation and argument of latitude
+    let mp = (134.963 + 13.064_993 * d) * RAD;
+    let elongation = (297.850 + 12.190_749 * d) * RAD;
+    let f = (93.272 + 13.229_350 * d) * RAD;
+    // sun's mean anomaly
+    let ms = (357.529 + 0.985_600 * d) * RAD;
+
+    let longitude = l
+        + (6.289 * mp.sin()
+            + 1.274 * (2.0 * elongation - mp).sin()
+            + 0.658 * (2.0 * elongation).sin()
+            + 0.214 * (2.0 * mp).sin()
+            - 0.186 * ms.sin()
+            - 0.114 * (2.0 * f).sin())
+            * RAD;
+    let latitude = 5.128 * RAD * f.sin();
+    let distance = 385_001.0
+        - 20_905.0 * mp.cos()
+        - 3_699.0 * (2.0 * elongation - mp).cos()
+        - 2_956.0 * (2.0 * elongation).cos();
+
+    (longitude, latitude, distance)
+}
+
+/// Altitude and azimuth of the moon's centre at the given time.
+#[must_use]
+pub fn moon_position(unix: i64, lat: f64, lon: f64) -> Position {
+    let d = days(unix);
+    let (longitude, latitude, _) = coordinates(d);
+    let (ra, dec) = equatorial(longitude, latitude);
+    horizontal(d, ra, dec, lat, lon)
+}
+
+/// Lunar phase at the given time, in the range 0..1 where 0 is new moon,
+/// 0.25 first quarter, 0.5 full moon and 0.75 last quarter.
+#[must_use]
+pub fn moon_phase(unix: i64) -> f64 {
+    let d = days(unix);
+    let (longitude, _, _) = coordinates(d);
+    let elongation = longitude - sun::longitude(d);
+    (elongation / (2.0 * PI)).rem_euclid(1.0)
+}
+
+/// Fraction of the moon's disc that is lit, for a phase from `moon_phase`.
+#[must_use]
+pub fn illumination(phase: f64) -> f64 {
+    (1.0 - (2.0 * PI * phase).cos()) / 2.0
+}
+
+/// Common name for a lunar phase in the range 0..1.
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn phase_name(phase: f64) -> &'static str {
+    const NAMES: [&str; 8] = [
+        "New Moon",
+        "Waxing Crescent",
+        "First Quarter",
+        "Waxing Gibbous",
+        "Full Moon",
+        "Waning Gibbous",
+        "Last Quarter",
+        "Waning Crescent",
+    ];
+    let index = (phase.rem_euclid(1.0) * 8.0).round() as usize % NAMES.len();
+    NAMES[index]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lunation {
+    New,
+    Full,
+}
+
+/// Time of the first new or full moon after the given time.
+#[must_use]
+pub fn next_lunation(after: i64, lunation: Lunation) -> i64 {
+    let jd = julian_day(after);
+    // lunations since the new moon of 2000-01-06, starting a little early
+    let mut k = ((jd - J2000) / SYNODIC_MONTH).floor() - 1.0;
+    if lunation == Lunation::Full {
+        k += 0.5;
+    }
+    loop {
+        let time = unix_time(true_lunation(k, lunation) - DELTA_T / SECONDS_PER_DAY);
+        if time > after {
+            return time;
+        }
+        k += 1.0;
+    }
+}
+
+// Meeus ch. 49: Julian ephemeris day of the lunation `k`, keeping the
+// principal periodic terms.
+#[allow(clippy::many_single_char_names)] // following Meeus' notation
+fn true_lunation(k: f64, lunation: Lunation) -> f64 {
+    let t = k / 1236.85;
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let t4 = t3 * t;
+
+    let jde = 2_451_550.097_66 + SYNODIC_MONTH * k + 0.000_154_37 * t2 - 0.000_000_150 * t3
+        + 0.000_000_000_73 * t4;
+    let e = 1.0 - 0.002_516 * t - 0.000_007_4 * t2;
+    let m = (2.5534 + 29.105_356_70 * k - 0.000_001_4 * t2 - 0.000_000_11 * t3) * RAD;
+    let mp = (201.5643 + 385.816_935_28 * k + 0.010_758_2 * t2 + 0.000_012_38 * t3
+        - 0.000_000_058 * t4)
+        * RAD;
+    let f = (160.7108 + 390.670_502_84 * k - 0.001_611_8 * t2 - 0.000_002_27 * t3
+        + 0.000_000_011 * t4)
+        * RAD;
+    let omega = (124.7746 - 1.563_755_88 * k + 0.002_067_2 * t2 + 0.000_002_15 * t3) * RAD;
+
+    let (c1, c2, c3, c4, c5, c6, c7) = match lunation {
+        Lunation::New => (
+            -0.407_20, 0.172_41, 0.016_08, 0.010_39, 0.007_39, -0.005_14, 0.002_08,
+        ),
+        Lunation::Full => (
+            -0.406_14, 0.173_02, 0.016_14, 0.010_43, 0.007_34, -0.005_15, 0.002_09,
+        ),
+    };
+
+    jde + c1 * mp.sin()
+        + c2 * e * m.sin()
+        + c3 * (2.0 * mp).sin()
+        + c4 * (2.0 * f).sin()
+        + c5 * e * (mp - m).sin()
+        + c6 * e * (mp + m).sin()
+        + c7 * e * e * (2.0 * m).sin()
+        - 0.001_11 * (mp - 2.0 * f).sin()
+        - 0.000_57 * (mp + 2.0 * f).sin()
+        + 0.000_56 * e * (2.0 * mp + m).sin()
+        - 0.000_42 * (3.0 * mp).sin()
+        + 0.000_42 * e * (m + 2.0 * f).sin()
+        + 0.000_38 * e * (m - 2.0 * f).sin()
+        - 0.000_24 * e * (2.0 * mp - m).sin()
+        - 0.000_17 * omega.sin()
+}
+
+// Degrees of the moon's geocentric altitude above the altitude at which it
+// appears to rise, which accounts for parallax and refraction.
+fn height_above_horizon(unix: i64, lat: f64, lon: f64) -> f64 {
+    let d = days(unix);
+    let (longitude, latitude, distance) = coordinates(d);
+    let (ra, dec) = equatorial(longitude, latitude);
+    let position = horizontal(d, ra, dec, lat, lon);
+
+    let parallax = (6378.14 / distance).asin() / RAD;
+    position.altitude - (0.7275 * parallax - 0.5667)
+}
+
+/// Moonrise and moonset within the 24 hours following `day_start`.
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+pub fn moon_times(day_start: i64, lat: f64, lon: f64) -> MoonTimes {
+    const STEP: i64 = 600;
+
+    let mut times = MoonTimes::default();
+    let mut t0 = day_start;
+    let mut h0 = height_above_horizon(t0, lat, lon);
+
+    while t0 < day_start + SECONDS_PER_DAY as i64 {
+        let t1 = t0 + STEP;
+        let h1 = height_above_horizon(t1, lat, lon);
+
+        if h0.signum() != h1.signum() {
+            // linear interpolation is good to well under a minute at this step
+            let crossing = t0 + ((h0 / (h0 - h1)) * STEP as f64) as i64;
+            if h0 < 0.0 {
+                times.rise.get_or_insert(crossing);
+            } else {
+                times.set.get_or_insert(crossing);
+            }
+        }
+
+        t0 = t1;
+        h0 = h1;
+    }
+
+    times
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_near(actual: i64, expected: i64, tolerance: i64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{actual} is not within {tolerance}s of {expected}"
+        );
+    }
+
+    #[test]
+    fn meeus_new_moon() {
+        // Meeus example 49.a: new moon at 1977-02-18 03:37:42 TD,
+        // or 03:36:54 UT with the 48s ΔT of the time.
+        let new = next_lunation(225_000_000, Lunation::New);
+        // the fixed modern ΔT puts us 21s early
+        assert_near(new, 225_085_014, 60);
+    }
+
+    #[test]
+    fn lunations_2024() {
+        // 2024-04-01T00:00:00Z
+        let start = 1_711_929_600;
+        // new moon 2024-04-08 18:21 UTC, full moon 2024-04-23 23:49 UTC
+        assert_near(next_lunation(start, Lunation::New), 1_712_600_460, 120);
+        assert_near(next_lunation(start, Lunation::Full), 1_713_916_140, 120);
+    }
+
+    #[test]
+    fn meeus_illumination() {
+        // Meeus example 48.a: on 1992-04-12 0h TD the moon is 67.86% lit
+        let lit = illumination(moon_phase(703_036_800));
+        assert!((lit - 0.6786).abs() < 0.01, "{lit}");
+    }
+
+    #[test]
+    fn london_moon_times() {
+        // 2024-06-21T00:00:00+01:00, the day before a full moon.
+        // HM Nautical Almanac Office: moonset 03:21, moonrise 21:44 BST
+        let times = moon_times(1_718_924_400, 51.5074, -0.1278);
+        assert_near(times.set.unwrap(), 1_718_936_460, 300);
+        assert_near(times.rise.unwrap(), 1_719_002_640, 300);
+    }
+
+    #[test]
+    fn phase_names() {
+        assert_eq!(phase_name(0.0), "New Moon");
+        assert_eq!(phase_name(0.98), "New Moon");
+        assert_eq!(phase_name(0.25), "First Quarter");
+        assert_eq!(phase_name(0.5), "Full Moon");
+        assert_eq!(phase_name(0.6), "Waning Gibbous");
+    }
+}
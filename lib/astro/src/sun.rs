@@ -0,0 +1,159 @@
+use std::f64::consts::PI;
+
+use super::time::{days, equatorial, horizontal, unix_time, J2000, OBLIQUITY, RAD};
+use super::Position;
+
+// altitudes of the sun's centre that define each event
+const SUNRISE: f64 = -0.833; // upper limb on the horizon, allowing for refraction
+const CIVIL: f64 = -6.0;
+const NAUTICAL: f64 = -12.0;
+const ASTRONOMICAL: f64 = -18.0;
+
+/// Times of the solar events on one day. Events that don't occur that day,
+/// such as sunset during the midnight sun, are `None`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SunTimes {
+    pub solar_noon: i64,
+    pub sunrise: Option<i64>,
+    pub sunset: Option<i64>,
+    pub civil_dawn: Option<i64>,
+    pub civil_dusk: Option<i64>,
+    pub nautical_dawn: Option<i64>,
+    pub nautical_dusk: Option<i64>,
+    pub astronomical_dawn: Option<i64>,
+    pub astronomical_dusk: Option<i64>,
+}
+
+impl SunTimes {
+    /// Seconds between sunrise and sunset.
+    #[must_use]
+    pub fn day_length(&self) -> Option<i64> {
+        Some(self.sunset? - self.sunrise?)
+    }
+}
+
+fn mean_anomaly(d: f64) -> f64 {
+    (357.5291 + 0.985_600_28 * d) * RAD
+}
+
+fn ecliptic_longitude(m: f64) -> f64 {
+    let center = (1.9148 * m.sin() + 0.02 * (2.0 * m).sin() + 0.0003 * (3.0 * m).sin()) * RAD;
+    // argument of perihelion
+    m + center + 102.9372 * RAD + PI
+}
+
+/// Ecliptic longitude of the sun, in radians.
+pub(crate) fn longitude(d: f64) -> f64 {
+    ecliptic_longitude(mean_anomaly(d))
+}
+
+/// Altitude and azimuth of the sun at the given time.
+#[must_use]
+pub fn sun_position(unix: i64, lat: f64, lon: f64) -> Position {
+    let d = days(unix);
+    let (ra, dec) = equatorial(longitude(d), 0.0);
+    horizontal(d, ra, dec, lat, lon)
+}
+
+/// Solar events for the day starting at `day_start`, which is usually local
+/// midnight.
+#[must_use]
+pub fn sun_times(day_start: i64, lat: f64, lon: f64) -> SunTimes {
+    let west = -lon * RAD;
+    let phi = lat * RAD;
+
+    // work from local noon so that we pick the right transit
+    let d = days(day_start + 43_200);
+    let cycle = (d - west / (2.0 * PI)).round();
+    let transit = west / (2.0 * PI) + cycle;
+
+    let m = mean_anomaly(transit);
+    let l = ecliptic_longitude(m);
+    let dec = (OBLIQUITY.sin() * l.sin()).asin();
+
+    let equation = 0.0053 * m.sin() - 0.0069 * (2.0 * l).sin();
+    let noon = J2000 + transit + equation;
+
+    // the morning and evening times the sun's centre is at `altitude`
+    let crossing = |altitude: f64| -> (Option<i64>, Option<i64>) {
+        let cos_w = ((altitude * RAD).sin() - phi.sin() * dec.sin()) / (phi.cos() * dec.cos());
+        if !(-1.0..=1.0).contains(&cos_w) {
+            return (None, None);
+        }
+        let set = J2000 + (cos_w.acos() + west) / (2.0 * PI) + cycle + equation;
+        let rise = noon - (set - noon);
+        (Some(unix_time(rise)), Some(unix_time(set)))
+    };
+
+    let (sunrise, sunset) = crossing(SUNRISE);
+    let (civil_dawn, civil_dusk) = crossing(CIVIL);
+    let (nautical_dawn, nautical_dusk) = crossing(NAUTICAL);
+    let (astronomical_dawn, astronomical_dusk) = crossing(ASTRONOMICAL);
+
+    SunTimes {
+        solar_noon: unix_time(noon),
+        sunrise,
+        sunset,
+        civil_dawn,
+        civil_dusk,
+        nautical_dawn,
+        nautical_dusk,
+        astronomical_dawn,
+        astronomical_dusk,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // 2024-06-21T00:00:00+01:00, the summer solstice in London
+    const LONDON_SOLSTICE: i64 = 1_718_924_400;
+    const LONDON: (f64, f64) = (51.5074, -0.1278);
+
+    fn assert_near(actual: Option<i64>, expected: i64, tolerance: i64) {
+        let actual = actual.expect("event should occur");
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{actual} is not within {tolerance}s of {expected}"
+        );
+    }
+
+    #[test]
+    fn london_solstice() {
+        let times = sun_times(LONDON_SOLSTICE, LONDON.0, LONDON.1);
+
+        // HM Nautical Almanac Office: sunrise 04:43, sunset 21:21 BST
+        assert_near(times.sunrise, 1_718_941_380, 120);
+        assert_near(times.sunset, 1_719_001_260, 120);
+        // solar noon 13:02 BST
+        assert_near(Some(times.solar_noon), 1_718_971_320, 60);
+        // the sun stays above -18° all night, so there is no astronomical night
+        assert_eq!(times.astronomical_dawn, None);
+        assert!(times.civil_dawn < times.sunrise);
+        assert!(times.nautical_dawn < times.civil_dawn);
+    }
+
+    #[test]
+    fn noon_altitude() {
+        let times = sun_times(LONDON_SOLSTICE, LONDON.0, LONDON.1);
+        let noon = sun_position(times.solar_noon, LONDON.0, LONDON.1);
+
+        // 90° - latitude + the solstice declination of 23.44°
+        assert!((noon.altitude - 61.93).abs() < 0.1, "{}", noon.altitude);
+        // due south
+        assert!((noon.azimuth - 180.0).abs() < 0.5, "{}", noon.azimuth);
+    }
+
+    #[test]
+    fn polar_night() {
+        // 2024-12-21T00:00:00+01:00 in Tromsø, where the noon sun stays
+        // about 3° below the horizon
+        let times = sun_times(1_734_735_600, 69.6492, 18.9553);
+
+        assert_eq!(times.sunrise, None);
+        assert_eq!(times.sunset, None);
+        assert!(times.civil_dawn.is_some());
+        assert_eq!(times.day_length(), None);
+    }
+}
@@ -0,0 +1,57 @@
+use std::f64::consts::PI;
+
+use super::Position;
+
+pub const RAD: f64 = PI / 180.0;
+pub const SECONDS_PER_DAY: f64 = 86400.0;
+// Julian day of the unix epoch
+const JD_UNIX_EPOCH: f64 = 2_440_587.5;
+// Julian day of J2000.0
+pub const J2000: f64 = 2_451_545.0;
+// TT - UT, close enough for the 2020s
+pub const DELTA_T: f64 = 69.0;
+// obliquity of the ecliptic at J2000
+pub const OBLIQUITY: f64 = 23.4397 * RAD;
+
+#[allow(clippy::cast_precision_loss)]
+pub fn julian_day(unix: i64) -> f64 {
+    unix as f64 / SECONDS_PER_DAY + JD_UNIX_EPOCH
+}
+
+#[allow(clippy::cast_possible_truncation)]
+pub fn unix_time(jd: f64) -> i64 {
+    ((jd - JD_UNIX_EPOCH) * SECONDS_PER_DAY).round() as i64
+}
+
+/// Days since J2000.0.
+pub fn days(unix: i64) -> f64 {
+    julian_day(unix) - J2000
+}
+
+/// Right ascension and declination, in radians, from ecliptic coordinates.
+pub fn equatorial(longitude: f64, latitude: f64) -> (f64, f64) {
+    let ra = (longitude.sin() * OBLIQUITY.cos() - latitude.tan() * OBLIQUITY.sin())
+        .atan2(longitude.cos());
+    let dec = (latitude.sin() * OBLIQUITY.cos()
+        + latitude.cos() * OBLIQUITY.sin() * longitude.sin())
+    .asin();
+    (ra, dec)
+}
+
+/// Altitude and azimuth of a body with the given equatorial coordinates.
+pub fn horizontal(d: f64, ra: f64, dec: f64, lat: f64, lon: f64) -> Position {
+    let sidereal = (280.16 + 360.985_623_5 * d + lon) * RAD;
+    let hour_angle = sidereal - ra;
+    let phi = lat * RAD;
+
+    let altitude = (phi.sin() * dec.sin() + phi.cos() * dec.cos() * hour_angle.cos()).asin();
+    // measured from south, westwards
+    let azimuth = hour_angle
+        .sin()
+        .atan2(hour_angle.cos() * phi.sin() - dec.tan() * phi.cos());
+
+    Position {
+        altitude: altitude / RAD,
+        azimuth: (azimuth / RAD + 180.0).rem_euclid(360.0),
+    }
+}
@@ -0,0 +1,407 @@
+/// Air pollutants reported by air-quality providers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pollutant {
+    Pm2_5,
+    Pm10,
+    O3,
+    No2,
+    So2,
+    Co,
+}
+
+impl Pollutant {
+    pub const ALL: [Pollutant; 6] = [
+        Pollutant::Pm2_5,
+        Pollutant::Pm10,
+        Pollutant::O3,
+        Pollutant::No2,
+        Pollutant::So2,
+        Pollutant::Co,
+    ];
+
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Pollutant::Pm2_5 => "PM2.5",
+            Pollutant::Pm10 => "PM10",
+            Pollutant::O3 => "O₃",
+            Pollutant::No2 => "NO₂",
+            Pollutant::So2 => "SO₂",
+            Pollutant::Co => "CO",
+        }
+    }
+
+    // µg/m³ per unit that the US EPA breakpoints are defined in
+    // (ppb for gases, ppm for CO), at 25°C and 1 atm.
+    fn us_unit_mass(self) -> f64 {
+        match self {
+            Pollutant::Pm2_5 | Pollutant::Pm10 => 1.0,
+            Pollutant::O3 => 1.96,
+            Pollutant::No2 => 1.88,
+            Pollutant::So2 => 2.62,
+            Pollutant::Co => 1145.0,
+        }
+    }
+}
+
+/// Pollutant concentrations, all in µg/m³.
+#[derive(Debug, Clone, Default)]
+pub struct Concentrations {
+    pub pm2_5: Option<f64>,
+    pub pm10: Option<f64>,
+    pub o3: Option<f64>,
+    pub no2: Option<f64>,
+    pub so2: Option<f64>,
+    pub co: Option<f64>,
+}
+
+impl Concentrations {
+    #[must_use]
+    pub fn get(&self, pollutant: Pollutant) -> Option<f64> {
+        match pollutant {
+            Pollutant::Pm2_5 => self.pm2_5,
+            Pollutant::Pm10 => self.pm10,
+            Pollutant::O3 => self.o3,
+            Pollutant::No2 => self.no2,
+            Pollutant::So2 => self.so2,
+            Pollutant::Co => self.co,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AirQualityPoint {
+    pub time: i64,
+    pub concentrations: Concentrations,
+}
+
+impl AirQualityPoint {
+    /// Overall index on the given scale, which is the worst of the
+    /// per-pollutant sub-indices.
+    #[must_use]
+    pub fn aqi(&self, scale: AqiScale) -> Option<Aqi> {
+        Pollutant::ALL
+            .iter()
+            .filter_map(|&p| self.sub_index(scale, p))
+            .max_by(|a, b| a.value.total_cmp(&b.value))
+    }
+
+    /// Index for a single pollutant on the given scale.
+    #[must_use]
+    pub fn sub_index(&self, scale: AqiScale, pollutant: Pollutant) -> Option<Aqi> {
+        let concentration = self.concentrations.get(pollutant)?;
+        let value = scale.index(pollutant, concentration)?;
+        Some(Aqi {
+            scale,
+            value,
+            level: scale.level(value),
+            dominant: pollutant,
+        })
+    }
+}
+
+pub type AirQualityBlock = Vec<AirQualityPoint>;
+
+/// The index scales an AQI can be expressed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AqiScale {
+    /// US EPA AQI, 0-500.
+    #[default]
+    UsEpa,
+    /// European Environment Agency AQI, 0-100+ in bands of 20.
+    European,
+}
+
+// (concentration low, concentration high, index low, index high)
+type Breakpoint = (f64, f64, f64, f64);
+
+// US EPA breakpoints, in the units given by `Pollutant::us_unit_mass`.
+// Uses the 2024 revision of the PM2.5 table.
+const US_PM2_5: &[Breakpoint] = &[
+    (0.0, 9.0, 0.0, 50.0),
+    (9.1, 35.4, 51.0, 100.0),
+    (35.5, 55.4, 101.0, 150.0),
+    (55.5, 125.4, 151.0, 200.0),
+    (125.5, 225.4, 201.0, 300.0),
+    (225.5, 325.4, 301.0, 500.0),
+];
+const US_PM10: &[Breakpoint] = &[
+    (0.0, 54.0, 0.0, 50.0),
+    (55.0, 154.0, 51.0, 100.0),
+    (155.0, 254.0, 101.0, 150.0),
+    (255.0, 354.0, 151.0, 200.0),
+    (355.0, 424.0, 201.0, 300.0),
+    (425.0, 604.0, 301.0, 500.0),
+];
+const US_O3: &[Breakpoint] = &[
+    (0.0, 54.0, 0.0, 50.0),
+    (55.0, 70.0, 51.0, 100.0),
+    (71.0, 85.0, 101.0, 150.0),
+    (86.0, 105.0, 151.0, 200.0),
+    (106.0, 200.0, 201.0, 300.0),
+];
+const US_NO2: &[Breakpoint] = &[
+    (0.0, 53.0, 0.0, 50.0),
+    (54.0, 100.0, 51.0, 100.0),
+    (101.0, 360.0, 101.0, 150.0),
+    (361.0, 649.0, 151.0, 200.0),
+    (650.0, 1249.0, 201.0, 300.0),
+    (1250.0, 2049.0, 301.0, 500.0),
+];
+const US_SO2: &[Breakpoint] = &[
+    (0.0, 35.0, 0.0, 50.0),
+    (36.0, 75.0, 51.0, 100.0),
+    (76.0, 185.0, 101.0, 150.0),
+    (186.0, 304.0, 151.0, 200.0),
+    (305.0, 604.0, 201.0, 300.0),
+    (605.0, 1004.0, 301.0, 500.0),
+];
+const US_CO: &[Breakpoint] = &[
+    (0.0, 4.4, 0.0, 50.0),
+    (4.5, 9.4, 51.0, 100.0),
+    (9.5, 12.4, 101.0, 150.0),
+    (12.5, 15.4, 151.0, 200.0),
+    (15.5, 30.4, 201.0, 300.0),
+    (30.5, 50.4, 301.0, 500.0),
+];
+
+// European band upper bounds in µg/m³, for the bands
+// good, fair, moderate, poor, very poor, extremely poor.
+const EU_PM2_5: [f64; 6] = [10.0, 20.0, 25.0, 50.0, 75.0, 800.0];
+const EU_PM10: [f64; 6] = [20.0, 40.0, 50.0, 100.0, 150.0, 1200.0];
+const EU_O3: [f64; 6] = [50.0, 100.0, 130.0, 240.0, 380.0, 800.0];
+const EU_NO2: [f64; 6] = [40.0, 90.0, 120.0, 230.0, 340.0, 1000.0];
+const EU_SO2: [f64; 6] = [100.0, 200.0, 350.0, 500.0, 750.0, 1250.0];
+
+const US_CATEGORIES: [&str; 6] = [
+    "Good",
+    "Moderate",
+    "Unhealthy for Sensitive Groups",
+    "Unhealthy",
+    "Very Unhealthy",
+    "Hazardous",
+];
+
+const EU_CATEGORIES: [&str; 6] = [
+    "Good",
+    "Fair",
+    "Moderate",
+    "Poor",
+    "Very Poor",
+    "Extremely Poor",
+];
+
+impl AqiScale {
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            AqiScale::UsEpa => "US EPA",
+            AqiScale::European => "European",
+        }
+    }
+
+    /// Upper end of the gauge for this scale.
+    #[must_use]
+    pub fn max(self) -> f64 {
+        match self {
+            AqiScale::UsEpa => 500.0,
+            AqiScale::European => 120.0,
+        }
+    }
+
+    /// Category level, from 0 (best) to 5 (worst).
+    #[must_use]
+    pub fn level(self, value: f64) -> usize {
+        let bounds: [f64; 5] = match self {
+            AqiScale::UsEpa => [50.0, 100.0, 150.0, 200.0, 300.0],
+            AqiScale::European => [20.0, 40.0, 60.0, 80.0, 100.0],
+        };
+        bounds.iter().filter(|&&b| value > b).count()
+    }
+
+    #[must_use]
+    pub fn category(self, level: usize) -> &'static str {
+        let categories = match self {
+            AqiScale::UsEpa => &US_CATEGORIES,
+            AqiScale::European => &EU_CATEGORIES,
+        };
+        categories[level.min(categories.len() - 1)]
+    }
+
+    // Index for a concentration in µg/m³, or `None` if the scale does
+    // not cover the pollutant.
+    //
+    // Note that both scales are defined over averaging periods (24h for
+    // particulates, 8h for O3 and CO under the EPA), whereas this is
+    // computed from a single hourly concentration.
+    fn index(self, pollutant: Pollutant, concentration: f64) -> Option<f64> {
+        match self {
+            AqiScale::UsEpa => {
+                let table = match pollutant {
+                    Pollutant::Pm2_5 => US_PM2_5,
+                    Pollutant::Pm10 => US_PM10,
+                    Pollutant::O3 => US_O3,
+                    Pollutant::No2 => US_NO2,
+                    Pollutant::So2 => US_SO2,
+                    Pollutant::Co => US_CO,
+                };
+                let c = truncate(pollutant, concentration / pollutant.us_unit_mass());
+                Some(us_index(table, c))
+            }
+            AqiScale::European => {
+                let bands = match pollutant {
+                    Pollutant::Pm2_5 => &EU_PM2_5,
+                    Pollutant::Pm10 => &EU_PM10,
+                    Pollutant::O3 => &EU_O3,
+                    Pollutant::No2 => &EU_NO2,
+                    Pollutant::So2 => &EU_SO2,
+                    Pollutant::Co => return None,
+                };
+                Some(european_index(bands, concentration))
+            }
+        }
+    }
+}
+
+// The EPA truncates concentrations to the precision of the breakpoint
+// tables before looking them up, which closes the gaps between rows.
+fn truncate(pollutant: Pollutant, c: f64) -> f64 {
+    match pollutant {
+        Pollutant::Pm2_5 | Pollutant::Co => (c * 10.0).trunc() / 10.0,
+        _ => c.trunc(),
+    }
+}
+
+fn us_index(table: &[Breakpoint], c: f64) -> f64 {
+    let c = c.max(0.0);
+    let (c_lo, c_hi, i_lo, i_hi) = table
+        .iter()
+        .find(|(_, hi, _, _)| c <= *hi)
+        .copied()
+        // beyond the table the index is capped at the top of the last row
+        .unwrap_or_else(|| {
+            let last = table[table.len() - 1];
+            (last.1, last.1, last.3, last.3)
+        });
+    if c_hi <= c_lo {
+        return i_hi;
+    }
+    ((i_hi - i_lo) / (c_hi - c_lo) * (c - c_lo) + i_lo).round()
+}
+
+fn european_index(bands: &[f64; 6], c: f64) -> f64 {
+    let c = c.max(0.0);
+    let mut lo = 0.0;
+    for (&hi, band) in bands.iter().zip(0u8..) {
+        if c <= hi {
+            return 20.0 * (f64::from(band) + (c - lo) / (hi - lo));
+        }
+        lo = hi;
+    }
+    AqiScale::European.max()
+}
+
+/// An air-quality index value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aqi {
+    pub scale: AqiScale,
+    pub value: f64,
+    /// Category level, from 0 (best) to 5 (worst).
+    pub level: usize,
+    /// The pollutant responsible for the index value.
+    pub dominant: Pollutant,
+}
+
+impl Aqi {
+    #[must_use]
+    pub fn category(&self) -> &'static str {
+        self.scale.category(self.level)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)] // index values are whole numbers
+mod test {
+    use super::*;
+
+    fn point(concentrations: Concentrations) -> AirQualityPoint {
+        AirQualityPoint {
+            time: 0,
+            concentrations,
+        }
+    }
+
+    #[test]
+    fn us_pm2_5_breakpoints() {
+        let aqi = |c| {
+            point(Concentrations {
+                pm2_5: Some(c),
+                ..Default::default()
+            })
+            .aqi(AqiScale::UsEpa)
+            .unwrap()
+        };
+        assert_eq!(aqi(0.0).value, 0.0);
+        assert_eq!(aqi(9.0).value, 50.0);
+        assert_eq!(aqi(9.1).value, 51.0);
+        assert_eq!(aqi(35.4).value, 100.0);
+        assert_eq!(aqi(35.45).value, 100.0);
+        assert_eq!(aqi(55.5).value, 151.0);
+        assert_eq!(aqi(55.5).category(), "Unhealthy");
+        assert_eq!(aqi(1000.0).value, 500.0);
+    }
+
+    #[test]
+    fn us_gas_conversion() {
+        // ~100.5 ppb of NO2 truncates to the top of the "Moderate" row
+        let aqi = point(Concentrations {
+            no2: Some(189.0),
+            ..Default::default()
+        })
+        .aqi(AqiScale::UsEpa)
+        .unwrap();
+        assert_eq!(aqi.value, 100.0);
+        assert_eq!(aqi.level, 1);
+    }
+
+    #[test]
+    fn dominant_pollutant_is_worst() {
+        let aqi = point(Concentrations {
+            pm2_5: Some(5.0),
+            pm10: Some(160.0),
+            o3: Some(20.0),
+            ..Default::default()
+        })
+        .aqi(AqiScale::UsEpa)
+        .unwrap();
+        assert_eq!(aqi.dominant, Pollutant::Pm10);
+        assert_eq!(aqi.category(), "Unhealthy for Sensitive Groups");
+    }
+
+    #[test]
+    fn european_bands() {
+        let aqi = |c| {
+            point(Concentrations {
+                pm10: Some(c),
+                ..Default::default()
+            })
+            .aqi(AqiScale::European)
+            .unwrap()
+        };
+        assert_eq!(aqi(10.0).value, 10.0);
+        assert_eq!(aqi(45.0).value, 50.0);
+        assert_eq!(aqi(45.0).category(), "Moderate");
+        assert_eq!(aqi(5000.0).value, 120.0);
+        assert_eq!(aqi(5000.0).category(), "Extremely Poor");
+    }
+
+    #[test]
+    fn european_ignores_co() {
+        let p = point(Concentrations {
+            co: Some(300.0),
+            ..Default::default()
+        });
+        assert!(p.aqi(AqiScale::European).is_none());
+        assert!(p.aqi(AqiScale::UsEpa).is_some());
+    }
+}
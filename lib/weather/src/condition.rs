@@ -0,0 +1,89 @@
+/// Weather conditions common to all providers, mapped from each provider's
+/// own icon codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Condition {
+    ClearDay,
+    ClearNight,
+    PartlyCloudyDay,
+    PartlyCloudyNight,
+    Cloudy,
+    Fog,
+    Wind,
+    Rain,
+    Sleet,
+    Snow,
+    Hail,
+    Thunderstorm,
+    #[default]
+    Unknown,
+}
+
+impl Condition {
+    /// Map a Pirate Weather icon code, including the extended set returned
+    /// with `icon=pirate`.
+    #[must_use]
+    pub fn from_pirate(icon: &str) -> Self {
+        match icon {
+            "clear-day" | "mostly-clear-day" => Condition::ClearDay,
+            "clear-night" | "mostly-clear-night" => Condition::ClearNight,
+            "partly-cloudy-day" => Condition::PartlyCloudyDay,
+            "partly-cloudy-night" => Condition::PartlyCloudyNight,
+            "cloudy" | "mostly-cloudy-day" | "mostly-cloudy-night" => Condition::Cloudy,
+            "fog" | "haze" | "smoke" | "mist" => Condition::Fog,
+            "wind" | "breezy" | "dangerous-wind" => Condition::Wind,
+            "sleet" | "mixed" | "freezing-rain" | "ice" => Condition::Sleet,
+            "snow" | "light-snow" | "heavy-snow" | "flurries" => Condition::Snow,
+            "hail" => Condition::Hail,
+            "thunderstorm" => Condition::Thunderstorm,
+            "rain" | "drizzle" | "light-rain" | "heavy-rain" | "precipitation" => Condition::Rain,
+            other => match other.strip_prefix("possible-") {
+                Some(rest) if rest.starts_with("thunderstorm") => Condition::Thunderstorm,
+                Some(rest) if rest.starts_with("snow") => Condition::Snow,
+                Some(rest) if rest.starts_with("sleet") => Condition::Sleet,
+                Some(_) => Condition::Rain,
+                None => Condition::Unknown,
+            },
+        }
+    }
+
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Condition::ClearDay | Condition::ClearNight => "Clear",
+            Condition::PartlyCloudyDay | Condition::PartlyCloudyNight => "Partly cloudy",
+            Condition::Cloudy => "Cloudy",
+            Condition::Fog => "Fog",
+            Condition::Wind => "Windy",
+            Condition::Rain => "Rain",
+            Condition::Sleet => "Sleet",
+            Condition::Snow => "Snow",
+            Condition::Hail => "Hail",
+            Condition::Thunderstorm => "Thunderstorm",
+            Condition::Unknown => "Unknown",
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pirate_codes() {
+        assert_eq!(Condition::from_pirate("clear-day"), Condition::ClearDay);
+        assert_eq!(
+            Condition::from_pirate("partly-cloudy-night"),
+            Condition::PartlyCloudyNight
+        );
+        assert_eq!(Condition::from_pirate("sleet"), Condition::Sleet);
+        assert_eq!(
+            Condition::from_pirate("possible-thunderstorm-day"),
+            Condition::Thunderstorm
+        );
+        assert_eq!(
+            Condition::from_pirate("possible-rain-night"),
+            Condition::Rain
+        );
+        assert_eq!(Condition::from_pirate("tornado"), Condition::Unknown);
+    }
+}
@@ -0,0 +1,267 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+// bumped on each exchange recorded, so viewers know when to look again
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+// the last exchange of each kind of request
+static EXCHANGES: Mutex<Vec<Exchange>> = Mutex::new(Vec::new());
+
+/// Kind of request made to a provider.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Request {
+    Forecast,
+    History,
+    AirQuality,
+}
+
+impl Request {
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Request::Forecast => "Pirate Weather forecast",
+            Request::History => "Pirate Weather history",
+            Request::AirQuality => "Open-Meteo air quality",
+        }
+    }
+}
+
+/// A request to a provider and the response to it, as received.
+#[derive(Clone, Debug)]
+pub struct Exchange {
+    pub request: Request,
+    /// URL requested, with any API key redacted.
+    pub url: String,
+    /// HTTP status, if a response was received.
+    pub status: Option<u16>,
+    pub latency_ms: f64,
+    /// Unix time the response arrived.
+    pub received: i64,
+    /// Body of the response, as sent.
+    pub body: String,
+    /// The response as it was parsed, if the type supports showing it.
+    pub parsed: Option<Value>,
+    /// Why the request or parsing failed.
+    pub error: Option<String>,
+}
+
+/// Count of exchanges recorded so far, which changes whenever `exchanges` does.
+#[must_use]
+pub fn generation() -> u64 {
+    GENERATION.load(Ordering::Relaxed)
+}
+
+/// The last exchange of each kind of request, in the order first made.
+///
+/// # Panics
+///
+/// If a thread panicked while recording an exchange.
+#[must_use]
+pub fn exchanges() -> Vec<Exchange> {
+    EXCHANGES.lock().expect("exchanges poisoned").clone()
+}
+
+fn record(exchange: Exchange) {
+    let mut exchanges = EXCHANGES.lock().expect("exchanges poisoned");
+    match exchanges.iter_mut().find(|e| e.request == exchange.request) {
+        Some(last) => *last = exchange,
+        None => exchanges.push(exchange),
+    }
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Attach the parsed form of the last response to `request`.
+pub(crate) fn record_parsed<T: Serialize>(request: Request, parsed: &T) {
+    let mut exchanges = EXCHANGES.lock().expect("exchanges poisoned");
+    if let Some(last) = exchanges.iter_mut().find(|e| e.request == request) {
+        last.parsed = serde_json::to_value(parsed).ok();
+        GENERATION.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn redact(url: &str, secret: Option<&str>) -> String {
+    match secret {
+        Some(secret) if !secret.is_empty() => url.replace(secret, "<redacted>"),
+        _ => url.to_string(),
+    }
+}
+
+// Record a response and parse its body.
+fn parse<T: DeserializeOwned>(mut exchange: Exchange) -> Result<T> {
+    let parsed = serde_json::from_str(&exchange.body);
+    if let Err(e) = &parsed {
+        exchange.error = Some(e.to_string());
+    }
+    record(exchange);
+    Ok(parsed?)
+}
+
+// Record a request that got no response. The error may quote the URL, so it
+// is redacted as well.
+#[allow(clippy::cast_possible_truncation)]
+fn failed(
+    request: Request,
+    url: &str,
+    secret: Option<&str>,
+    latency_ms: f64,
+    error: &anyhow::Error,
+) {
+    record(Exchange {
+        request,
+        url: redact(url, secret),
+        status: None,
+        latency_ms,
+        received: now_ms() as i64 / 1000,
+        body: String::new(),
+        parsed: None,
+        error: Some(redact(&error.to_string(), secret)),
+    });
+}
+
+#[cfg(target_arch = "wasm32")]
+fn now_ms() -> f64 {
+    js_sys::Date::now()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(clippy::cast_precision_loss)]
+fn now_ms() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0.0, |d| d.as_millis() as f64)
+}
+
+/// GET `url` and parse the JSON response, recording the exchange.
+#[cfg(target_arch = "wasm32")]
+#[allow(clippy::cast_possible_truncation)]
+pub(crate) async fn get_json<T: DeserializeOwned>(
+    request: Request,
+    url: &str,
+    secret: Option<&str>,
+) -> Result<T> {
+    use gloo_net::http::Request as HttpRequest;
+
+    let start = now_ms();
+    let sent = async {
+        let response = HttpRequest::get(url).send().await?;
+        let status = response.status();
+        Ok::<_, anyhow::Error>((status, response.text().await?))
+    };
+    match sent.await {
+        Ok((status, body)) => parse(Exchange {
+            request,
+            url: redact(url, secret),
+            status: Some(status),
+            latency_ms: now_ms() - start,
+            received: now_ms() as i64 / 1000,
+            body,
+            parsed: None,
+            error: None,
+        }),
+        Err(e) => {
+            failed(request, url, secret, now_ms() - start, &e);
+            Err(e)
+        }
+    }
+}
+
+/// GET `url` and parse the JSON response, recording the exchange.
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(clippy::cast_possible_truncation)]
+pub(crate) async fn get_json<T: DeserializeOwned>(
+    request: Request,
+    url: &str,
+    secret: Option<&str>,
+) -> Result<T> {
+    use reqwest::Client;
+    use std::time::Instant;
+
+    let start = Instant::now();
+    let sent = async {
+        let response = Client::new().get(url).send().await?;
+        let status = response.status().as_u16();
+        Ok::<_, anyhow::Error>((status, response.text().await?))
+    };
+    let result = sent.await;
+    let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
+    match result {
+        Ok((status, body)) => parse(Exchange {
+            request,
+            url: redact(url, secret),
+            status: Some(status),
+            latency_ms,
+            received: now_ms() as i64 / 1000,
+            body,
+            parsed: None,
+            error: None,
+        }),
+        Err(e) => {
+            failed(request, url, secret, latency_ms, &e);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn redacts_the_key() {
+        assert_eq!(
+            redact("https://api/forecast/s3cret/1,2?units=us", Some("s3cret")),
+            "https://api/forecast/<redacted>/1,2?units=us"
+        );
+        assert_eq!(
+            redact("https://api/air?lat=1", None),
+            "https://api/air?lat=1"
+        );
+    }
+
+    #[test]
+    fn redacts_the_key_from_errors() {
+        let url = "https://api/forecast/s3cret/1,2?units=us";
+        let error = anyhow::anyhow!("error sending request for url ({url})");
+        failed(Request::Forecast, url, Some("s3cret"), 1.0, &error);
+
+        let last = exchanges()
+            .into_iter()
+            .find(|e| e.request == Request::Forecast)
+            .unwrap();
+        let message = last.error.unwrap();
+        assert!(!message.contains("s3cret"), "{message}");
+        assert!(message.contains("<redacted>"));
+        assert!(!last.url.contains("s3cret"));
+    }
+
+    #[test]
+    fn keeps_the_last_exchange_of_each_request() {
+        let exchange = |body: &str| Exchange {
+            request: Request::History,
+            url: String::new(),
+            status: Some(200),
+            latency_ms: 1.0,
+            received: 0,
+            body: body.into(),
+            parsed: None,
+            error: None,
+        };
+        let before = generation();
+        let first: Value = parse(exchange("{\"a\": 1}")).unwrap();
+        assert!(parse::<Value>(exchange("{\"a\":")).is_err());
+        record_parsed(Request::History, &first);
+
+        let last = exchanges()
+            .into_iter()
+            .find(|e| e.request == Request::History)
+            .unwrap();
+        assert_eq!(last.body, "{\"a\":");
+        assert!(last.error.is_some());
+        assert_eq!(last.parsed, Some(first));
+        assert!(generation() >= before + 3);
+    }
+}
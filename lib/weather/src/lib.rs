@@ -4,9 +4,16 @@
 use anyhow::Result;
 use async_trait::async_trait;
 
+mod air;
+mod condition;
+pub mod inspect;
+mod openmeteo;
 mod pirate;
 
-pub use pirate::{ForecastResponse as PirateData, PirateWeather};
+pub use air::{AirQualityBlock, AirQualityPoint, Aqi, AqiScale, Concentrations, Pollutant};
+pub use condition::Condition;
+pub use openmeteo::{AirQualityResponse as OpenMeteoAirData, OpenMeteo};
+pub use pirate::{Alert, ForecastResponse as PirateData, PirateWeather, Units};
 
 // At present, just using the pirate data.
 // In the future if we support other weather APIs, we'll
@@ -22,6 +29,19 @@ pub trait WeatherData {
     fn daily(&self) -> Option<&DataBlock>;
 
     fn time(&self) -> (&str, f64);
+
+    /// Latitude and longitude the data was fetched for.
+    fn location(&self) -> (f64, f64);
+
+    /// Summary and overall condition of the days ahead, if the provider gives one.
+    fn outlook(&self) -> Option<(&str, Condition)> {
+        None
+    }
+
+    /// Weather alerts in effect for the location.
+    fn alerts(&self) -> &[Alert] {
+        &[]
+    }
 }
 
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
@@ -31,3 +51,28 @@ pub trait WeatherFetch {
 
     async fn fetch_weather(lat: f64, lon: f64) -> Result<Self::Output>;
 }
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait HistoricalFetch {
+    type Output;
+
+    /// Observed weather for the local day containing `time`, a unix timestamp in the past.
+    async fn fetch_historical(lat: f64, lon: f64, time: i64) -> Result<Self::Output>;
+}
+
+pub trait AirQualityData {
+    fn current(&self) -> Option<&AirQualityPoint>;
+
+    fn hourly(&self) -> Option<&AirQualityBlock>;
+
+    fn time(&self) -> (&str, f64);
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait AirQualityFetch {
+    type Output;
+
+    async fn fetch_air_quality(lat: f64, lon: f64) -> Result<Self::Output>;
+}
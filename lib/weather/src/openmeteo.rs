@@ -0,0 +1,198 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::Deserialize;
+use tracing::info;
+
+use super::inspect::{self, Request};
+use super::{AirQualityBlock, AirQualityData, AirQualityFetch, AirQualityPoint, Concentrations};
+
+const AIR_QUALITY_URL: &str = "https://air-quality-api.open-meteo.com/v1/air-quality";
+
+const POLLUTANTS: &str = "pm2_5,pm10,ozone,nitrogen_dioxide,sulphur_dioxide,carbon_monoxide";
+
+pub struct OpenMeteo {}
+
+fn air_quality_url(lat: f64, lon: f64) -> String {
+    format!(
+        "{AIR_QUALITY_URL}?latitude={lat}&longitude={lon}\
+         &current={POLLUTANTS}&hourly={POLLUTANTS}\
+         &timezone=auto&timeformat=unixtime&forecast_days=4"
+    )
+}
+
+// Send is not compatible with WASM
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl AirQualityFetch for OpenMeteo {
+    type Output = AirQualityResponse;
+
+    #[cfg(target_arch = "wasm32")]
+    async fn fetch_air_quality(lat: f64, lon: f64) -> Result<Self::Output> {
+        let response =
+            inspect::get_json(Request::AirQuality, &air_quality_url(lat, lon), None).await?;
+
+        info!("fetched open-meteo air quality data");
+
+        Ok(response)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn fetch_air_quality(lat: f64, lon: f64) -> Result<Self::Output> {
+        let response =
+            inspect::get_json(Request::AirQuality, &air_quality_url(lat, lon), None).await?;
+
+        info!("fetched open-meteo air quality data");
+
+        Ok(response)
+    }
+}
+
+/// Air quality data, normalized from the column-oriented Open-Meteo response.
+#[derive(Debug, Deserialize, Default)]
+#[serde(from = "RawResponse")]
+pub struct AirQualityResponse {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub timezone: String,
+    /// UTC offset in hours
+    pub offset: f64,
+    pub current: Option<AirQualityPoint>,
+    pub hourly: Option<AirQualityBlock>,
+}
+
+impl AirQualityData for AirQualityResponse {
+    fn current(&self) -> Option<&AirQualityPoint> {
+        self.current.as_ref()
+    }
+
+    fn hourly(&self) -> Option<&AirQualityBlock> {
+        self.hourly.as_ref()
+    }
+
+    fn time(&self) -> (&str, f64) {
+        (&self.timezone, self.offset)
+    }
+}
+
+#[derive(Deserialize)]
+struct RawResponse {
+    latitude: f64,
+    longitude: f64,
+    timezone: String,
+    utc_offset_seconds: i32,
+    current: Option<RawCurrent>,
+    hourly: Option<RawHourly>,
+}
+
+#[derive(Deserialize)]
+struct RawCurrent {
+    time: i64,
+    pm2_5: Option<f64>,
+    pm10: Option<f64>,
+    ozone: Option<f64>,
+    nitrogen_dioxide: Option<f64>,
+    sulphur_dioxide: Option<f64>,
+    carbon_monoxide: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct RawHourly {
+    time: Vec<i64>,
+    #[serde(default)]
+    pm2_5: Vec<Option<f64>>,
+    #[serde(default)]
+    pm10: Vec<Option<f64>>,
+    #[serde(default)]
+    ozone: Vec<Option<f64>>,
+    #[serde(default)]
+    nitrogen_dioxide: Vec<Option<f64>>,
+    #[serde(default)]
+    sulphur_dioxide: Vec<Option<f64>>,
+    #[serde(default)]
+    carbon_monoxide: Vec<Option<f64>>,
+}
+
+impl From<RawResponse> for AirQualityResponse {
+    fn from(raw: RawResponse) -> Self {
+        let current = raw.current.map(|c| AirQualityPoint {
+            time: c.time,
+            concentrations: Concentrations {
+                pm2_5: c.pm2_5,
+                pm10: c.pm10,
+                o3: c.ozone,
+                no2: c.nitrogen_dioxide,
+                so2: c.sulphur_dioxide,
+                co: c.carbon_monoxide,
+            },
+        });
+
+        let hourly = raw.hourly.map(|h| {
+            let at = |column: &[Option<f64>], i: usize| column.get(i).copied().flatten();
+            h.time
+                .iter()
+                .enumerate()
+                .map(|(i, &time)| AirQualityPoint {
+                    time,
+                    concentrations: Concentrations {
+                        pm2_5: at(&h.pm2_5, i),
+                        pm10: at(&h.pm10, i),
+                        o3: at(&h.ozone, i),
+                        no2: at(&h.nitrogen_dioxide, i),
+                        so2: at(&h.sulphur_dioxide, i),
+                        co: at(&h.carbon_monoxide, i),
+                    },
+                })
+                .collect()
+        });
+
+        Self {
+            latitude: raw.latitude,
+            longitude: raw.longitude,
+            timezone: raw.timezone,
+            offset: f64::from(raw.utc_offset_seconds) / 3600.0,
+            current,
+            hourly,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)] // values are copied, not computed
+mod test {
+    use super::*;
+
+    #[test]
+    fn hourly_columns_become_points() {
+        let response: AirQualityResponse = serde_json::from_str(
+            r#"{
+                "latitude": 37.25, "longitude": -115.75, "timezone": "America/Los_Angeles",
+                "utc_offset_seconds": -25200,
+                "current": {"time": 1760900400, "pm2_5": 9.0, "ozone": 80.0},
+                "hourly": {
+                    "time": [1760900400, 1760904000, 1760907600],
+                    "pm2_5": [9.0, null, 12.5],
+                    "pm10": [20.0, 22.0],
+                    "ozone": [80.0, 85.0, 90.0]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(response.time(), ("America/Los_Angeles", -7.0));
+        let current = response.current().unwrap();
+        assert_eq!(current.concentrations.pm2_5, Some(9.0));
+        assert_eq!(current.concentrations.no2, None);
+
+        let hours = response.hourly().unwrap();
+        assert_eq!(hours.len(), 3);
+        let times: Vec<_> = hours.iter().map(|h| h.time).collect();
+        assert_eq!(times, [1_760_900_400, 1_760_904_000, 1_760_907_600]);
+        // each column lines up with the times, whatever is missing from it
+        assert_eq!(hours[0].concentrations.pm10, Some(20.0));
+        assert_eq!(hours[1].concentrations.pm2_5, None);
+        assert_eq!(hours[1].concentrations.o3, Some(85.0));
+        assert_eq!(hours[2].concentrations.pm2_5, Some(12.5));
+        assert_eq!(hours[2].concentrations.pm10, None);
+        assert_eq!(hours[2].concentrations.co, None);
+    }
+}
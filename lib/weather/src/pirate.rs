@@ -1,60 +1,190 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tracing::info;
 
-use super::{WeatherData, WeatherFetch};
+use super::inspect::{self, Request};
+use super::{Condition, HistoricalFetch, WeatherData, WeatherFetch};
 
 const BASE_URL: &str = "https://api.pirateweather.net";
+const TIME_MACHINE_URL: &str = "https://timemachine.pirateweather.net";
 
 pub struct PirateWeather {}
 
+/// Units Pirate Weather gives values in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Units {
+    /// Fahrenheit, mph, miles and inches.
+    #[default]
+    Us,
+    /// Celsius, m/s, kilometres and millimetres.
+    Si,
+    /// As `Si`, with wind in km/h.
+    Ca,
+    /// As `Si`, with wind in mph and visibility in miles.
+    Uk,
+}
+
+impl Units {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Units::Us => "us",
+            Units::Si => "si",
+            Units::Ca => "ca",
+            Units::Uk => "uk",
+        }
+    }
+
+    #[must_use]
+    pub fn temperature(self) -> &'static str {
+        match self {
+            Units::Us => "°F",
+            Units::Si | Units::Ca | Units::Uk => "°C",
+        }
+    }
+
+    #[must_use]
+    pub fn speed(self) -> &'static str {
+        match self {
+            Units::Us | Units::Uk => "mph",
+            Units::Si => "m/s",
+            Units::Ca => "km/h",
+        }
+    }
+
+    #[must_use]
+    pub fn distance(self) -> &'static str {
+        match self {
+            Units::Us | Units::Uk => "mi",
+            Units::Si | Units::Ca => "km",
+        }
+    }
+}
+
+impl std::str::FromStr for Units {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "us" => Ok(Units::Us),
+            "si" => Ok(Units::Si),
+            "ca" => Ok(Units::Ca),
+            "uk" | "uk2" => Ok(Units::Uk),
+            _ => Err(anyhow::anyhow!(
+                "Unknown units {s}, expected us, si, ca or uk"
+            )),
+        }
+    }
+}
+
+impl PirateWeather {
+    /// Forecast for a location, in the given units, with 48 hours of hourly data.
+    ///
+    /// # Errors
+    ///
+    /// If the API key isn't set, the request fails or the response can't be parsed.
+    pub async fn fetch_forecast(lat: f64, lon: f64, units: Units) -> Result<ForecastResponse> {
+        Self::forecast(lat, lon, units, false).await
+    }
+
+    /// As `fetch_forecast`, with hourly data for the whole week rather than 48 hours.
+    ///
+    /// # Errors
+    ///
+    /// If the API key isn't set, the request fails or the response can't be parsed.
+    pub async fn fetch_extended_forecast(
+        lat: f64,
+        lon: f64,
+        units: Units,
+    ) -> Result<ForecastResponse> {
+        Self::forecast(lat, lon, units, true).await
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    async fn forecast(lat: f64, lon: f64, units: Units, extend: bool) -> Result<ForecastResponse> {
+        let api_key = env!("PIRATEWEATHER_API_KEY");
+        let url = forecast_url(api_key, lat, lon, units, extend);
+
+        let response: ForecastResponse =
+            inspect::get_json(Request::Forecast, &url, Some(api_key)).await?;
+        inspect::record_parsed(Request::Forecast, &response);
+
+        info!("aye... fetched pirate weather data");
+
+        Ok(response)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn forecast(lat: f64, lon: f64, units: Units, extend: bool) -> Result<ForecastResponse> {
+        let api_key =
+            std::env::var("PIRATEWEATHER_API_KEY").context("PIRATEWEATHER_API_KEY is not set")?;
+        let url = forecast_url(&api_key, lat, lon, units, extend);
+
+        let forecast: ForecastResponse =
+            inspect::get_json(Request::Forecast, &url, Some(&api_key)).await?;
+        inspect::record_parsed(Request::Forecast, &forecast);
+
+        info!("aye... fetched pirate weather data");
+
+        Ok(forecast)
+    }
+}
+
+fn forecast_url(api_key: &str, lat: f64, lon: f64, units: Units, extend: bool) -> String {
+    let units = units.as_str();
+    let extend = if extend { "&extend=hourly" } else { "" };
+    format!("{BASE_URL}/forecast/{api_key}/{lat},{lon}?units={units}{extend}")
+}
+
 // Send is not compatible with WASM
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl WeatherFetch for PirateWeather {
     type Output = ForecastResponse;
 
-    #[cfg(target_arch = "wasm32")]
     async fn fetch_weather(lat: f64, lon: f64) -> Result<Self::Output> {
-        use gloo_net::http::Request;
+        Self::fetch_forecast(lat, lon, Units::Us).await
+    }
+}
+
+// Send is not compatible with WASM
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl HistoricalFetch for PirateWeather {
+    type Output = ForecastResponse;
 
+    #[cfg(target_arch = "wasm32")]
+    async fn fetch_historical(lat: f64, lon: f64, time: i64) -> Result<Self::Output> {
         let api_key = env!("PIRATEWEATHER_API_KEY");
-        let url = format!("{BASE_URL}/forecast/{api_key}/{lat},{lon}?units=us");
+        let url = format!("{TIME_MACHINE_URL}/forecast/{api_key}/{lat},{lon},{time}?units=us");
 
-        let response = Request::get(&url)
-            .send()
-            .await?
-            .json::<ForecastResponse>()
-            .await?;
+        let response: ForecastResponse =
+            inspect::get_json(Request::History, &url, Some(api_key)).await?;
+        inspect::record_parsed(Request::History, &response);
 
-        info!("aye... fetched pirate weather data");
+        info!("aye... fetched pirate weather history for {time}");
 
         Ok(response)
     }
 
     #[cfg(not(target_arch = "wasm32"))]
-    async fn fetch_weather(lat: f64, lon: f64) -> Result<Self::Output> {
-        use reqwest::Client;
+    async fn fetch_historical(lat: f64, lon: f64, time: i64) -> Result<Self::Output> {
+        let api_key =
+            std::env::var("PIRATEWEATHER_API_KEY").context("PIRATEWEATHER_API_KEY is not set")?;
+        let url = format!("{TIME_MACHINE_URL}/forecast/{api_key}/{lat},{lon},{time}?units=us");
 
-        let api_key = std::env::var("PIRATEWEATHER_API_KEY")?;
-        let url = format!("{BASE_URL}/forecast/{api_key}/{lat},{lon}?units=us");
+        let forecast: ForecastResponse =
+            inspect::get_json(Request::History, &url, Some(&api_key)).await?;
+        inspect::record_parsed(Request::History, &forecast);
 
-        let client = Client::new();
-        let forecast = client
-            .get(&url)
-            .send()
-            .await?
-            .json::<ForecastResponse>()
-            .await?;
-
-        info!("aye... fetched pirate weather data");
+        info!("aye... fetched pirate weather history for {time}");
 
         Ok(forecast)
     }
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Deserialize, Serialize, Default)]
 pub struct ForecastResponse {
     pub latitude: f64,
     pub longitude: f64,
@@ -85,16 +215,30 @@ impl WeatherData for ForecastResponse {
     fn time(&self) -> (&str, f64) {
         (&self.timezone, self.offset)
     }
+
+    fn location(&self) -> (f64, f64) {
+        (self.latitude, self.longitude)
+    }
+
+    fn outlook(&self) -> Option<(&str, Condition)> {
+        self.daily
+            .as_ref()
+            .map(|d| (d.summary.as_str(), Condition::from_pirate(&d.icon)))
+    }
+
+    fn alerts(&self) -> &[Alert] {
+        self.alerts.as_deref().unwrap_or_default()
+    }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct DataBlock {
     pub summary: String,
     pub icon: String,
     pub data: Vec<DataPoint>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct DataPoint {
     pub time: i64,
     pub summary: Option<String>,
@@ -213,7 +357,17 @@ pub struct DataPoint {
     pub current_day_snow: Option<f64>,
 }
 
-#[derive(Debug, Deserialize)]
+impl DataPoint {
+    /// Condition shown by this point's icon.
+    #[must_use]
+    pub fn condition(&self) -> Condition {
+        self.icon
+            .as_deref()
+            .map_or(Condition::Unknown, Condition::from_pirate)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Alert {
     pub title: String,
     pub regions: Vec<String>,
@@ -224,7 +378,7 @@ pub struct Alert {
     pub uri: String,
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Deserialize, Serialize, Default)]
 pub struct Flags {
     pub units: String,
     pub version: String,
@@ -237,7 +391,7 @@ pub struct Flags {
     pub process_time: Option<i64>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct SourceIdx {
     pub x: i64,
     pub y: i64,
@@ -1,8 +1,11 @@
 use std::marker::PhantomData;
 
 use anyhow::{Context, Result};
+use chrono::{NaiveDate, Utc};
+use chrono_tz::Tz;
 use eframe::Frame;
 use egui::Context as Ctx;
+use futures_util::future::try_join_all;
 use tokio::runtime::{Handle, Runtime};
 use tokio::sync::{
     mpsc,
@@ -10,8 +13,10 @@ use tokio::sync::{
 };
 use tracing::{error, info};
 
-use super::{LogsView, WeatherView};
-use lib_weather::{WeatherData, WeatherFetch};
+use super::{ArtView, InspectorView, LogsView, ThemeEditor, WeatherView};
+use crate::theme::{Theme, THEME_KEY};
+use crate::{LogEvent, LOG_LEVELS_KEY};
+use lib_weather::{AirQualityData, AirQualityFetch, HistoricalFetch, WeatherData, WeatherFetch};
 
 /// State machine for fetching weather data
 #[derive(Default, PartialEq)]
@@ -26,13 +31,21 @@ pub enum FetchState {
 }
 
 /// Manages intersection between the UI state and weather data.
-pub struct AppController<D, F>
+pub struct AppController<D, F, Q>
 where
-    D: WeatherData + Default + Sync + 'static,
-    F: WeatherFetch<Output = D>,
+    D: WeatherData + Default + Send + Sync + 'static,
+    F: WeatherFetch<Output = D> + HistoricalFetch<Output = D>,
+    Q: AirQualityFetch,
+    Q::Output: AirQualityData + Default + Sync + 'static,
 {
     sender: Sender<Result<D>>,
     receiver: Receiver<Result<D>>,
+    air_sender: Sender<Result<Q::Output>>,
+    air_receiver: Receiver<Result<Q::Output>>,
+    history_sender: Sender<Result<Vec<D>>>,
+    history_receiver: Receiver<Result<Vec<D>>>,
+    /// index of the past day the widgets are showing during playback
+    history_day: Option<usize>,
     runtime: Option<Runtime>,
     state: AppState,
     /// Weather data
@@ -40,10 +53,12 @@ where
     _fetcher: PhantomData<F>,
 }
 
-impl<D, F> AppController<D, F>
+impl<D, F, Q> AppController<D, F, Q>
 where
-    D: WeatherData + Default + Sync + 'static,
-    F: WeatherFetch<Output = D>,
+    D: WeatherData + Default + Send + Sync + 'static,
+    F: WeatherFetch<Output = D> + HistoricalFetch<Output = D>,
+    Q: AirQualityFetch,
+    Q::Output: AirQualityData + Default + Sync + 'static,
 {
     /// # Panics
     ///
@@ -52,12 +67,19 @@ where
     pub fn new(state: AppState, runtime: Option<Runtime>) -> Self {
         let data = D::default();
         let (sender, receiver) = tokio::sync::watch::channel(Ok(D::default()));
+        let (air_sender, air_receiver) = tokio::sync::watch::channel(Ok(Q::Output::default()));
+        let (history_sender, history_receiver) = tokio::sync::watch::channel(Ok(Vec::new()));
 
         info!("Initializing app");
 
         let mut controller = Self {
             sender,
             receiver,
+            air_sender,
+            air_receiver,
+            history_sender,
+            history_receiver,
+            history_day: None,
             runtime,
             state,
             data,
@@ -70,38 +92,116 @@ where
     }
 
     fn fetch(&mut self) {
+        let Some((lat, lon)) = self.location() else {
+            self.state.fetch_state = FetchState::Completed;
+            return;
+        };
+
+        self.state.update_location(lat, lon);
+
+        let sender = self.sender.clone();
+        let air_sender = self.air_sender.clone();
+
+        info!("Fetching weather data at ({lat}, {lon})");
+
+        cfg_if::cfg_if! {
+            if #[cfg(target_arch = "wasm32")] {
+                wasm_bindgen_futures::spawn_local(async move {
+                    let response = F::fetch_weather(lat, lon).await;
+                    if let Err(err) = sender.send(response) {
+                        error!("{err}");
+                    }
+                    let response = Q::fetch_air_quality(lat, lon).await;
+                    if let Err(err) = air_sender.send(response) {
+                        error!("{err}");
+                    }
+                });
+            } else {
+                self.runtime.as_ref().unwrap().block_on(async {
+                    let response = F::fetch_weather(lat, lon).await;
+                    if let Err(err) = sender.send(response) {
+                        error!("{err}");
+                    }
+                    let response = Q::fetch_air_quality(lat, lon).await;
+                    if let Err(err) = air_sender.send(response) {
+                        error!("{err}");
+                    }
+                });
+            }
+        }
+    }
+
+    fn location(&mut self) -> Option<(f64, f64)> {
         // validate lat and lon first.
         // we could do this in the UI update area when fetch button clicked,
         // but then we'd either have to store a second set of vars for the parsed
         // float values, or re-parse them here. Neither of which are ideal.
-        let (lat, lon) = match validate_lat_lon_input(
+        match validate_lat_lon_input(
             &self.state.weather_view.latitude_str,
             &self.state.weather_view.longitude_str,
         ) {
-            Ok(res) => res,
+            Ok(res) => Some(res),
             Err(err) => {
                 error!("Invalid location submitted: {err}");
                 self.state.weather_view.location_error_modal_open = true;
-                self.state.fetch_state = FetchState::Completed;
-                return;
+                None
+            }
+        }
+    }
+
+    // today at the location, by the forecast's timezone when it's for the same
+    // place, otherwise by local mean time from the longitude
+    #[allow(clippy::cast_possible_truncation)]
+    fn today_at(&self, lat: f64, lon: f64) -> NaiveDate {
+        let now = Utc::now();
+        if let Ok(data) = &*self.receiver.borrow() {
+            let (data_lat, data_lon) = data.location();
+            if (data_lat - lat).abs() < 0.01 && (data_lon - lon).abs() < 0.01 {
+                if let Ok(tz) = data.time().0.parse::<Tz>() {
+                    return now.with_timezone(&tz).date_naive();
+                }
             }
+        }
+        (now + chrono::Duration::seconds((lon * 240.0) as i64)).date_naive()
+    }
+
+    fn fetch_history(&mut self, dates: Vec<NaiveDate>) {
+        let Some((lat, lon)) = self.location() else {
+            self.state.weather_view.playback.failed();
+            return;
+        };
+        let today = self.today_at(lat, lon);
+        let Some(dates) = self.state.weather_view.playback.past_days(dates, today) else {
+            return;
         };
 
-        let sender = self.sender.clone();
+        // ask for around local noon, so each request lands inside the day wanted
+        #[allow(clippy::cast_possible_truncation)]
+        let times: Vec<i64> = dates
+            .iter()
+            .filter_map(|date| date.and_hms_opt(12, 0, 0))
+            .map(|noon| noon.and_utc().timestamp() - (lon * 240.0) as i64)
+            .collect();
 
-        info!("Fetching weather data at ({lat}, {lon})");
+        let sender = self.history_sender.clone();
+
+        info!(
+            "Fetching {} days of weather history at ({lat}, {lon})",
+            times.len()
+        );
 
         cfg_if::cfg_if! {
             if #[cfg(target_arch = "wasm32")] {
                 wasm_bindgen_futures::spawn_local(async move {
-                    let response = F::fetch_weather(lat, lon).await;
+                    let response = fetch_days::<F>(lat, lon, times).await;
                     if let Err(err) = sender.send(response) {
                         error!("{err}");
                     }
                 });
             } else {
-                self.runtime.as_ref().unwrap().block_on(async {
-                    let response = F::fetch_weather(lat, lon).await;
+                // the days can take a while, so fetch them off the UI thread
+                self.runtime.as_ref().unwrap().spawn(async move {
+                    let response = fetch_days::<F>(lat, lon, times).await;
                     if let Err(err) = sender.send(response) {
                         error!("{err}");
                     }
@@ -110,6 +210,61 @@ where
         }
     }
 
+    fn update_playback(&mut self, ctx: &Ctx) {
+        if let Some(dates) = self.state.weather_view.playback.take_request() {
+            self.fetch_history(dates);
+        }
+
+        if let Ok(true) = self.history_receiver.has_changed() {
+            let playback = &mut self.state.weather_view.playback;
+            match &*self.history_receiver.borrow_and_update() {
+                Ok(days) => {
+                    let hours = days
+                        .iter()
+                        .map(|day| {
+                            day.hourly()
+                                .map(|hours| hours.iter().map(|h| h.time).collect())
+                                .unwrap_or_default()
+                        })
+                        .collect();
+                    let timezone = days.first().map_or("", |day| day.time().0);
+                    playback.loaded(hours, timezone);
+                }
+                Err(err) => {
+                    error!("{err}");
+                    playback.failed();
+                }
+            }
+            self.history_day = None;
+        }
+
+        let dt = ctx.input(|i| i.stable_dt);
+        if let Some((day, time)) = self.state.weather_view.playback.tick(dt) {
+            if let Ok(days) = &*self.history_receiver.borrow() {
+                if let Some(data) = days.get(day) {
+                    // only hand over a whole day when playback crosses into it
+                    if self.history_day != Some(day) {
+                        self.history_day = Some(day);
+                        self.state.update_history(data);
+                    }
+                    self.state.weather_view.widgets.select_time(Some(time));
+                }
+            }
+        }
+
+        if self.state.weather_view.playback.take_stopped() {
+            self.history_day = None;
+            if let Ok(data) = &*self.receiver.borrow() {
+                self.state.update_data(data);
+            }
+            self.state.weather_view.widgets.select_time(None);
+        }
+
+        if self.state.weather_view.playback.is_playing() {
+            ctx.request_repaint();
+        }
+    }
+
     fn update(&mut self, ctx: &Ctx) {
         if self.state.fetch_state == FetchState::Requested {
             self.state.fetch_state = FetchState::InProgress;
@@ -118,6 +273,8 @@ where
 
         if let Ok(true) = self.receiver.has_changed() {
             match &*self.receiver.borrow_and_update() {
+                // kept in the channel for when playback ends
+                Ok(_) if self.state.weather_view.playback.is_active() => {}
                 Ok(data) => {
                     self.state.update_data(data);
                 }
@@ -128,21 +285,50 @@ where
             self.state.fetch_state = FetchState::Completed;
         }
 
+        if let Ok(true) = self.air_receiver.has_changed() {
+            match &*self.air_receiver.borrow_and_update() {
+                Ok(data) => {
+                    self.state.update_air_quality(data);
+                }
+                Err(err) => {
+                    error!("{err}");
+                }
+            }
+        }
+
+        self.update_playback(ctx);
+
         self.state.update(ctx);
     }
 }
 
-impl<D, F> eframe::App for AppController<D, F>
+async fn fetch_days<F: HistoricalFetch>(
+    lat: f64,
+    lon: f64,
+    times: Vec<i64>,
+) -> Result<Vec<F::Output>> {
+    try_join_all(
+        times
+            .into_iter()
+            .map(|time| F::fetch_historical(lat, lon, time)),
+    )
+    .await
+}
+
+impl<D, F, Q> eframe::App for AppController<D, F, Q>
 where
-    D: WeatherData + Default + Sync + 'static,
-    F: WeatherFetch<Output = D>,
+    D: WeatherData + Default + Send + Sync + 'static,
+    F: WeatherFetch<Output = D> + HistoricalFetch<Output = D>,
+    Q: AirQualityFetch,
+    Q::Output: AirQualityData + Default + Sync + 'static,
 {
-    // TODO: re-enable for feature to save state
-    // fn save(&mut self, storage: &mut dyn eframe::Storage) {
-    //     let state = self.state.lock().unwrap();
-
-    //     eframe::set_value(storage, eframe::APP_KEY, &*state);
-    // }
+    // TODO: save the rest of the state
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, THEME_KEY, &self.state.theme_editor.theme);
+        if let Some(filter) = self.state.logs_view.filter() {
+            eframe::set_value(storage, LOG_LEVELS_KEY, &filter.save());
+        }
+    }
 
     fn update(&mut self, ctx: &Ctx, _frame: &mut Frame) {
         self.update(ctx);
@@ -153,16 +339,19 @@ where
 enum View {
     #[default]
     Weather,
+    Art,
+    Inspector,
     Log,
 }
 
 pub struct AppState {
     // UI view state
     active_view: View,
-    log_view_selected: bool,
-    weather_view_selected: bool,
     pub weather_view: WeatherView,
+    pub art_view: ArtView,
+    pub inspector_view: InspectorView,
     pub logs_view: LogsView,
+    pub theme_editor: ThemeEditor,
     /// state of the weather data fetch operation
     fetch_state: FetchState,
 }
@@ -170,24 +359,43 @@ pub struct AppState {
 impl AppState {
     /// Called once before the first frame.
     #[must_use]
-    pub fn new(logrx: mpsc::Receiver<String>, rt_handle: Option<&Handle>) -> Self {
+    pub fn new(logrx: mpsc::Receiver<LogEvent>, rt_handle: Option<&Handle>) -> Self {
         // TODO: re-enable for feature to save state
         // Load previous app state (if any).
         // if let Some(storage) = cc.storage {
         //     return eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
         // }
 
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(handle) = rt_handle {
+            crate::export::set_runtime(handle.clone());
+        }
+
         Self {
-            weather_view_selected: true,
             weather_view: WeatherView::new(rt_handle.cloned()),
+            art_view: ArtView::default(),
+            inspector_view: InspectorView::default(),
             logs_view: LogsView::new(logrx),
+            theme_editor: ThemeEditor::new(Theme::default()),
             active_view: View::default(),
-            log_view_selected: false,
             fetch_state: FetchState::default(),
         }
     }
 
+    /// Restore what was saved from the last run.
+    pub fn restore(&mut self, storage: Option<&dyn eframe::Storage>) {
+        if let Some(theme) = storage.and_then(|s| eframe::get_value::<Theme>(s, THEME_KEY)) {
+            self.theme_editor = ThemeEditor::new(theme);
+        }
+        let levels = storage.and_then(|s| eframe::get_value::<String>(s, LOG_LEVELS_KEY));
+        if let (Some(levels), Some(filter)) = (levels, self.logs_view.filter_mut()) {
+            filter.restore(&levels);
+        }
+    }
+
     fn update(&mut self, ctx: &Ctx) {
+        self.theme_editor.apply(ctx);
+
         // ensures the cached logs are up to date before the actual log view is selected
         // in the UI so that re-painting of the logs view doesn't lag behind.
         self.logs_view.check_logs();
@@ -196,23 +404,17 @@ impl AppState {
             // Top menu bar
             egui::TopBottomPanel::top("menu_bar").show(ui.ctx(), |ui| {
                 ui.horizontal(|ui| {
-                    // hopefully there is a cleaner way to acheive this:
-                    if ui
-                        .toggle_value(&mut self.weather_view_selected, "Weather")
-                        .clicked()
-                    {
-                        self.active_view = View::Weather;
-                        self.log_view_selected = false;
-                        self.weather_view_selected = true;
-                    }
-                    if ui
-                        .toggle_value(&mut self.log_view_selected, "Log")
-                        .clicked()
-                    {
-                        self.active_view = View::Log;
-                        self.weather_view_selected = false;
-                        self.log_view_selected = true;
+                    for (view, label) in [
+                        (View::Weather, "Weather"),
+                        (View::Art, "Art"),
+                        (View::Inspector, "Inspector"),
+                        (View::Log, "Log"),
+                    ] {
+                        ui.selectable_value(&mut self.active_view, view, label);
                     }
+
+                    ui.separator();
+                    ui.toggle_value(&mut self.theme_editor.open, "Theme");
                 });
             });
 
@@ -221,15 +423,37 @@ impl AppState {
                 View::Weather => {
                     self.weather_view.update(ui, &mut self.fetch_state);
                 }
+                View::Art => {
+                    self.art_view.update(ui);
+                }
+                View::Inspector => {
+                    self.inspector_view.update(ui);
+                }
                 View::Log => {
                     self.logs_view.update(ui);
                 }
             });
+
+            self.theme_editor.show(ui.ctx());
         });
     }
 
     fn update_data<D: WeatherData>(&mut self, data: &D) {
         self.weather_view.widgets.update_data(data);
+        self.art_view.update_data(data);
+    }
+
+    fn update_history<D: WeatherData>(&mut self, data: &D) {
+        self.weather_view.widgets.update_history(data);
+        self.art_view.update_data(data);
+    }
+
+    fn update_air_quality<A: AirQualityData>(&mut self, data: &A) {
+        self.weather_view.widgets.update_air_quality(data);
+    }
+
+    fn update_location(&mut self, lat: f64, lon: f64) {
+        self.weather_view.widgets.update_location(lat, lon);
     }
 }
 
@@ -250,7 +474,7 @@ mod test {
     use anyhow::anyhow;
     use egui_kittest::kittest::Queryable;
     use egui_kittest::Harness;
-    use lib_weather::PirateData;
+    use lib_weather::{OpenMeteoAirData, PirateData};
     use tokio::runtime::Builder;
 
     use super::*;
@@ -308,6 +532,15 @@ mod test {
         }
     }
 
+    #[async_trait::async_trait]
+    impl HistoricalFetch for StubWeatherFails {
+        type Output = PirateData;
+
+        async fn fetch_historical(_lat: f64, _lon: f64, _time: i64) -> Result<Self::Output> {
+            Err(anyhow!("Failed to fetch weather history"))
+        }
+    }
+
     struct StubWeatherSucceeds {}
 
     #[async_trait::async_trait]
@@ -319,21 +552,46 @@ mod test {
         }
     }
 
-    fn setup<T: lib_weather::WeatherFetch<Output = PirateData>>() -> AppController<PirateData, T> {
-        let (_logtx, logrx) = mpsc::channel::<String>(100);
+    #[async_trait::async_trait]
+    impl HistoricalFetch for StubWeatherSucceeds {
+        type Output = PirateData;
+
+        async fn fetch_historical(_lat: f64, _lon: f64, _time: i64) -> Result<Self::Output> {
+            Ok(PirateData::default())
+        }
+    }
+
+    struct StubAirQuality {}
+
+    #[async_trait::async_trait]
+    impl AirQualityFetch for StubAirQuality {
+        type Output = OpenMeteoAirData;
+
+        async fn fetch_air_quality(_lat: f64, _lon: f64) -> Result<Self::Output> {
+            Ok(OpenMeteoAirData::default())
+        }
+    }
+
+    fn setup<T>() -> AppController<PirateData, T, StubAirQuality>
+    where
+        T: WeatherFetch<Output = PirateData> + HistoricalFetch<Output = PirateData>,
+    {
+        let (_logtx, logrx) = mpsc::channel::<LogEvent>(100);
         let runtime = Builder::new_current_thread()
             .enable_all()
             .build()
             .expect("Failed to build runtime");
         let state = AppState::new(logrx, Some(runtime.handle()));
 
-        AppController::<PirateData, T>::new(state, Some(runtime))
+        AppController::<PirateData, T, StubAirQuality>::new(state, Some(runtime))
     }
 
-    fn fetch_mark_completed<D, F>(initial_state: AppController<D, F>)
+    fn fetch_mark_completed<D, F, Q>(initial_state: AppController<D, F, Q>)
     where
-        D: WeatherData + Default + Sync + 'static,
-        F: WeatherFetch<Output = D>,
+        D: WeatherData + Default + Send + Sync + 'static,
+        F: WeatherFetch<Output = D> + HistoricalFetch<Output = D>,
+        Q: AirQualityFetch,
+        Q::Output: AirQualityData + Default + Sync + 'static,
     {
         let mut harness = Harness::new_state(
             |ctx, initial_state| {
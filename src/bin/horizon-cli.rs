@@ -0,0 +1,256 @@
+#![warn(clippy::pedantic)]
+#![warn(clippy::all, rust_2018_idioms)]
+
+// Current conditions and forecasts in the terminal, for scripts.
+
+#[cfg(not(target_arch = "wasm32"))]
+mod cli {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use anyhow::{anyhow, Context, Result};
+    use clap::{Args, Parser, Subcommand};
+    use tokio::net::TcpListener;
+    use tokio::runtime::{Builder, Runtime};
+    use tracing_subscriber::EnvFilter;
+
+    use horizon::{ColorMap, Dashboard, Location, Report, Server, Table, Theme};
+    use lib_weather::{OpenMeteo, PirateData, PirateWeather, Units};
+
+    #[derive(Parser)]
+    #[command(name = "horizon-cli", version, about)]
+    struct Cli {
+        #[command(subcommand)]
+        command: Command,
+    }
+
+    #[derive(Subcommand)]
+    enum Command {
+        /// Current conditions.
+        Now(Options),
+        /// Daily forecast, or hourly with --hourly.
+        Forecast {
+            /// Each hour instead of each day.
+            #[arg(long)]
+            hourly: bool,
+            /// Days ahead to show.
+            #[arg(long, default_value_t = 7)]
+            days: usize,
+            #[command(flatten)]
+            options: Options,
+        },
+        /// Live dashboard, refreshed in the background.
+        Tui {
+            /// Minutes between refreshes.
+            #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+            refresh: u64,
+            /// Theme exported from the app, as JSON or TOML, to colour temperatures with.
+            #[arg(long)]
+            theme: Option<PathBuf>,
+            /// Colour temperatures with viridis, cividis or freezing, over the theme's own.
+            #[arg(long)]
+            colormap: Option<ColorMap>,
+            #[command(flatten)]
+            place: Place,
+        },
+        /// HTTP server giving the forecasts for one or more places as JSON,
+        /// so several clients can share one provider's quota.
+        Serve {
+            /// A place to serve, as name=lat,lon. Repeat for more; the first is the default.
+            #[arg(long = "location", required = true, allow_hyphen_values = true)]
+            locations: Vec<Location>,
+            /// Address to listen on.
+            #[arg(long, default_value = "127.0.0.1:8080")]
+            bind: String,
+            /// Minutes between refreshes.
+            #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+            refresh: u64,
+            /// Also serve /metrics for Prometheus.
+            #[arg(long)]
+            metrics: bool,
+        },
+    }
+
+    #[derive(Args)]
+    struct Options {
+        #[command(flatten)]
+        place: Place,
+        /// Print JSON instead of a table.
+        #[arg(long, conflicts_with = "csv")]
+        json: bool,
+        /// Print CSV instead of a table.
+        #[arg(long)]
+        csv: bool,
+    }
+
+    #[derive(Args)]
+    struct Place {
+        /// Latitude, in degrees north.
+        #[arg(long, allow_hyphen_values = true, requires = "lon")]
+        lat: Option<f64>,
+        /// Longitude, in degrees east.
+        #[arg(long, allow_hyphen_values = true, requires = "lat")]
+        lon: Option<f64>,
+        /// Find the location from this machine's IP address.
+        #[arg(long, conflicts_with_all = ["lat", "lon"])]
+        geolocate: bool,
+        /// One of us, si, ca or uk.
+        #[arg(long, default_value = "us")]
+        units: Units,
+    }
+
+    pub fn run() -> Result<()> {
+        let cli = Cli::parse();
+        let (report, options) = match cli.command {
+            Command::Now(options) => (Report::Now, options),
+            Command::Forecast {
+                hourly,
+                days,
+                options,
+            } => {
+                let report = if hourly {
+                    Report::Hourly(days)
+                } else {
+                    Report::Daily(days)
+                };
+                (report, options)
+            }
+            Command::Tui {
+                refresh,
+                theme,
+                colormap,
+                place,
+            } => {
+                let mut theme = match theme {
+                    Some(path) => {
+                        let text = std::fs::read_to_string(&path)
+                            .with_context(|| format!("Failed to read {}", path.display()))?;
+                        Theme::parse(&text)?
+                    }
+                    None => Theme::dark(),
+                };
+                if colormap.is_some() {
+                    theme.temperature_scale = colormap;
+                }
+                let runtime = runtime()?;
+                let location = runtime.block_on(location(&place))?;
+                drop(runtime);
+                return Dashboard::new(
+                    location,
+                    place.units,
+                    Duration::from_secs(refresh).saturating_mul(60),
+                )
+                .with_theme(theme)
+                .run();
+            }
+            Command::Serve {
+                locations,
+                bind,
+                refresh,
+                metrics,
+            } => {
+                let server = Server::<PirateData, PirateWeather, OpenMeteo>::new(
+                    locations,
+                    Duration::from_secs(refresh).saturating_mul(60),
+                )?;
+                return serve(
+                    if metrics {
+                        server.with_metrics()
+                    } else {
+                        server
+                    },
+                    &bind,
+                );
+            }
+        };
+
+        let units = options.place.units;
+        let data = runtime()?.block_on(async {
+            let (lat, lon) = location(&options.place).await?;
+            match report {
+                // the plain forecast only has the next 48 hours
+                Report::Hourly(days) if days > 2 => {
+                    PirateWeather::fetch_extended_forecast(lat, lon, units).await
+                }
+                _ => PirateWeather::fetch_forecast(lat, lon, units).await,
+            }
+        })?;
+
+        let table = Table::new(&data, report, units);
+        if let Report::Hourly(days) = report {
+            if table.rows.len() < days * 24 {
+                eprintln!(
+                    "Only {} hours of forecast available, fewer than the {days} days asked for",
+                    table.rows.len()
+                );
+            }
+        }
+        if options.json {
+            let json = match (report, table.to_json().as_slice()) {
+                // a single object for the current conditions, for easy querying
+                (Report::Now, [now]) => serde_json::to_string_pretty(now)?,
+                (_, rows) => serde_json::to_string_pretty(rows)?,
+            };
+            println!("{json}");
+        } else if options.csv {
+            print!("{}", table.to_csv());
+        } else {
+            print!("{}", table.to_text());
+        }
+        Ok(())
+    }
+
+    fn serve(server: Server<PirateData, PirateWeather, OpenMeteo>, bind: &str) -> Result<()> {
+        tracing_subscriber::fmt()
+            .with_env_filter(
+                EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+            )
+            .init();
+        let runtime = Runtime::new().context("Failed to build runtime")?;
+        runtime.block_on(async {
+            let listener = TcpListener::bind(bind)
+                .await
+                .with_context(|| format!("Failed to listen on {bind}"))?;
+            server.run(listener).await
+        })
+    }
+
+    fn runtime() -> Result<Runtime> {
+        Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("Failed to build runtime")
+    }
+
+    async fn location(place: &Place) -> Result<(f64, f64)> {
+        if let (Some(lat), Some(lon)) = (place.lat, place.lon) {
+            return Ok((lat, lon));
+        }
+        if !place.geolocate {
+            return Err(anyhow!(
+                "Give a location with --lat and --lon, or --geolocate"
+            ));
+        }
+        let geo = lib_geolocate::get_geo_location().await?;
+        let parse = |value: &str| {
+            value
+                .parse::<f64>()
+                .with_context(|| format!("Invalid coordinate from geolocation: {value}"))
+        };
+        Ok((
+            parse(&geo.location.latitude)?,
+            parse(&geo.location.longitude)?,
+        ))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    if let Err(e) = cli::run() {
+        eprintln!("Error: {e:#}");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
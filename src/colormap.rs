@@ -0,0 +1,213 @@
+use egui::Color32;
+use serde::{Deserialize, Serialize};
+
+// range of temperatures the scales span, in °C
+const COLDEST: f64 = -30.0;
+const HOTTEST: f64 = 45.0;
+
+/// Unit a temperature is given in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum TemperatureUnit {
+    #[default]
+    Fahrenheit,
+    Celsius,
+}
+
+impl TemperatureUnit {
+    #[must_use]
+    pub fn to_celsius(self, temp: f64) -> f64 {
+        match self {
+            TemperatureUnit::Fahrenheit => (temp - 32.0) * 5.0 / 9.0,
+            TemperatureUnit::Celsius => temp,
+        }
+    }
+
+    #[must_use]
+    pub fn to_fahrenheit(self, temp: f64) -> f64 {
+        match self {
+            TemperatureUnit::Fahrenheit => temp,
+            TemperatureUnit::Celsius => temp * 9.0 / 5.0 + 32.0,
+        }
+    }
+}
+
+/// Named colour scales for temperatures, all safe for the common forms of
+/// colour blindness.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum ColorMap {
+    /// Dark purple through teal to yellow, evenly increasing in lightness.
+    Viridis,
+    /// Navy through grey to yellow, the same for most colour-blind viewers.
+    Cividis,
+    /// Blue below freezing, white at freezing, red above.
+    Freezing,
+}
+
+impl ColorMap {
+    pub const ALL: [ColorMap; 3] = [ColorMap::Viridis, ColorMap::Cividis, ColorMap::Freezing];
+
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorMap::Viridis => "Viridis",
+            ColorMap::Cividis => "Cividis",
+            ColorMap::Freezing => "Diverging at freezing",
+        }
+    }
+
+    /// Colour for `temp`, given in `unit`.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn color(self, temp: f64, unit: TemperatureUnit) -> Color32 {
+        let celsius = unit.to_celsius(temp).clamp(COLDEST, HOTTEST);
+        let t = match self {
+            ColorMap::Viridis | ColorMap::Cividis => (celsius - COLDEST) / (HOTTEST - COLDEST),
+            // each side of freezing gets half the scale, so 0°C is always the midpoint
+            ColorMap::Freezing if celsius < 0.0 => 0.5 * (celsius - COLDEST) / -COLDEST,
+            ColorMap::Freezing => 0.5 + 0.5 * celsius / HOTTEST,
+        };
+        sample(self.stops(), t as f32)
+    }
+
+    // Evenly spaced samples of each scale.
+    fn stops(self) -> &'static [Color32] {
+        const VIRIDIS: [Color32; 9] = [
+            Color32::from_rgb(68, 1, 84),
+            Color32::from_rgb(70, 50, 126),
+            Color32::from_rgb(59, 82, 139),
+            Color32::from_rgb(44, 114, 142),
+            Color32::from_rgb(33, 145, 140),
+            Color32::from_rgb(40, 174, 128),
+            Color32::from_rgb(94, 201, 98),
+            Color32::from_rgb(173, 220, 48),
+            Color32::from_rgb(253, 231, 37),
+        ];
+        const CIVIDIS: [Color32; 9] = [
+            Color32::from_rgb(0, 32, 77),
+            Color32::from_rgb(0, 50, 111),
+            Color32::from_rgb(57, 72, 107),
+            Color32::from_rgb(87, 92, 109),
+            Color32::from_rgb(112, 113, 115),
+            Color32::from_rgb(138, 135, 121),
+            Color32::from_rgb(166, 157, 117),
+            Color32::from_rgb(198, 181, 102),
+            Color32::from_rgb(253, 231, 55),
+        ];
+        // ColorBrewer's RdBu, cold end first
+        const FREEZING: [Color32; 9] = [
+            Color32::from_rgb(33, 102, 172),
+            Color32::from_rgb(67, 147, 195),
+            Color32::from_rgb(146, 197, 222),
+            Color32::from_rgb(209, 229, 240),
+            Color32::from_rgb(247, 247, 247),
+            Color32::from_rgb(253, 219, 199),
+            Color32::from_rgb(244, 165, 130),
+            Color32::from_rgb(214, 96, 77),
+            Color32::from_rgb(178, 24, 43),
+        ];
+        match self {
+            ColorMap::Viridis => &VIRIDIS,
+            ColorMap::Cividis => &CIVIDIS,
+            ColorMap::Freezing => &FREEZING,
+        }
+    }
+}
+
+impl std::str::FromStr for ColorMap {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "viridis" => Ok(ColorMap::Viridis),
+            "cividis" => Ok(ColorMap::Cividis),
+            "freezing" => Ok(ColorMap::Freezing),
+            _ => Err(anyhow::anyhow!(
+                "Unknown colour map {s}, expected viridis, cividis or freezing"
+            )),
+        }
+    }
+}
+
+/// Colour at `t`, from 0 to 1, along evenly spaced `stops`.
+#[must_use]
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+pub fn sample(stops: &[Color32], t: f32) -> Color32 {
+    match stops {
+        [] => Color32::TRANSPARENT,
+        [only] => *only,
+        _ => {
+            let position = t.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+            let low = (position.floor() as usize).min(stops.len() - 2);
+            lerp(stops[low], stops[low + 1], position - low as f32)
+        }
+    }
+}
+
+fn lerp(from: Color32, to: Color32, t: f32) -> Color32 {
+    let [r0, g0, b0, a0] = from.to_array();
+    let [r1, g1, b1, a1] = to.to_array();
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let mix = |a: u8, b: u8| (f32::from(a) + (f32::from(b) - f32::from(a)) * t).round() as u8;
+    Color32::from_rgba_premultiplied(mix(r0, r1), mix(g0, g1), mix(b0, b1), mix(a0, a1))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // relative luminance, as WCAG defines it
+    fn luminance(color: Color32) -> f32 {
+        let linear = egui::Rgba::from(color);
+        0.2126 * linear.r() + 0.7152 * linear.g() + 0.0722 * linear.b()
+    }
+
+    #[test]
+    fn sequential_scales_get_lighter() {
+        for map in [ColorMap::Viridis, ColorMap::Cividis] {
+            let mut last = 0.0;
+            for temp in (-30..=45).step_by(5) {
+                let l = luminance(map.color(f64::from(temp), TemperatureUnit::Celsius));
+                assert!(l > last, "{map:?} darkens at {temp}°C");
+                last = l;
+            }
+        }
+    }
+
+    #[test]
+    fn parses_names() {
+        assert_eq!("Cividis".parse::<ColorMap>().unwrap(), ColorMap::Cividis);
+        assert_eq!("freezing".parse::<ColorMap>().unwrap(), ColorMap::Freezing);
+        assert!("jet".parse::<ColorMap>().is_err());
+    }
+
+    #[test]
+    fn units_agree() {
+        for map in ColorMap::ALL {
+            assert_eq!(
+                map.color(32.0, TemperatureUnit::Fahrenheit),
+                map.color(0.0, TemperatureUnit::Celsius)
+            );
+            assert_eq!(
+                map.color(-40.0, TemperatureUnit::Fahrenheit),
+                map.color(-40.0, TemperatureUnit::Celsius)
+            );
+        }
+    }
+
+    #[test]
+    fn freezing_is_the_midpoint() {
+        let white = Color32::from_rgb(247, 247, 247);
+        assert_eq!(
+            ColorMap::Freezing.color(0.0, TemperatureUnit::Celsius),
+            white
+        );
+        assert_eq!(
+            ColorMap::Freezing.color(-100.0, TemperatureUnit::Celsius),
+            Color32::from_rgb(33, 102, 172)
+        );
+    }
+}
@@ -0,0 +1,510 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+use egui::epaint::{ClippedShape, ColorMode, Mesh, TextShape};
+use egui::{
+    Color32, ColorImage, Context, Event, FontFamily, Pos2, Rect, Shape, Stroke, Ui, ViewportCommand,
+};
+use tracing::error;
+
+/// Image formats widgets can be exported to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ExportFormat {
+    Png,
+    Svg,
+}
+
+impl ExportFormat {
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Png => "png",
+            ExportFormat::Svg => "svg",
+        }
+    }
+}
+
+/// File name for an export of `what`, stamped with the local time.
+pub(crate) fn file_name(what: &str, format: ExportFormat) -> String {
+    format!(
+        "horizon-{}-{}.{}",
+        what.replace(|c: char| !c.is_alphanumeric(), "-"),
+        chrono::Local::now().format("%Y%m%d-%H%M"),
+        format.extension()
+    )
+}
+
+/// A button per export format, returning the one clicked.
+pub(crate) fn buttons(ui: &mut Ui) -> Option<ExportFormat> {
+    let mut clicked = None;
+    for format in [ExportFormat::Png, ExportFormat::Svg] {
+        if ui
+            .button(format!("Export as {}", format.extension().to_uppercase()))
+            .clicked()
+        {
+            clicked = Some(format);
+        }
+    }
+    clicked
+}
+
+/// A screenshot asked of the viewport, saved as a PNG of `rect` once it arrives.
+pub(crate) struct Screenshot {
+    rect: Rect,
+    file_name: String,
+}
+
+impl Screenshot {
+    pub(crate) fn request(ctx: &Context, rect: Rect, file_name: String) -> Self {
+        ctx.send_viewport_cmd(ViewportCommand::Screenshot(egui::UserData::default()));
+        Self { rect, file_name }
+    }
+
+    /// Save the screenshot if it arrived this frame, returning whether it did.
+    pub(crate) fn poll(&self, ctx: &Context) -> bool {
+        let image = ctx.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                Event::Screenshot { image, .. } => Some(image.clone()),
+                _ => None,
+            })
+        });
+        let Some(image) = image else {
+            return false;
+        };
+        let image = image.region(&self.rect, Some(ctx.pixels_per_point()));
+        if let Err(err) = png(&image).and_then(|bytes| save(&self.file_name, &bytes)) {
+            error!("Failed to export {}: {err}", self.file_name);
+        }
+        true
+    }
+}
+
+/// Encode a screenshot as a PNG.
+pub(crate) fn png(image: &ColorImage) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let [width, height] = image.size;
+    let mut encoder = png::Encoder::new(&mut bytes, u32::try_from(width)?, u32::try_from(height)?);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(image.as_raw())?;
+    Ok(bytes)
+}
+
+/// Render the shapes egui painted inside `area` as an SVG document.
+pub(crate) fn svg(shapes: &[ClippedShape], area: Rect, background: Color32) -> String {
+    let mut clips: Vec<Rect> = Vec::new();
+    let mut body = String::new();
+
+    for clipped in shapes {
+        let clip = clips
+            .iter()
+            .position(|r| *r == clipped.clip_rect)
+            .unwrap_or_else(|| {
+                clips.push(clipped.clip_rect);
+                clips.len() - 1
+            });
+        let mut shape = String::new();
+        write_shape(&mut shape, &clipped.shape);
+        if !shape.is_empty() {
+            let _ = writeln!(body, r#"<g clip-path="url(#clip{clip})">{shape}</g>"#);
+        }
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="{x} {y} {w} {h}">"#,
+        x = area.min.x,
+        y = area.min.y,
+        w = area.width(),
+        h = area.height(),
+    );
+    out.push_str("<defs>\n");
+    for (i, clip) in clips.iter().enumerate() {
+        let _ = writeln!(
+            out,
+            r#"<clipPath id="clip{i}"><rect x="{}" y="{}" width="{}" height="{}"/></clipPath>"#,
+            clip.min.x,
+            clip.min.y,
+            clip.width(),
+            clip.height()
+        );
+    }
+    out.push_str("</defs>\n");
+    let _ = writeln!(
+        out,
+        r#"<rect x="{}" y="{}" width="{}" height="{}" {}/>"#,
+        area.min.x,
+        area.min.y,
+        area.width(),
+        area.height(),
+        fill(background)
+    );
+    out.push_str(&body);
+    out.push_str("</svg>\n");
+    out
+}
+
+fn write_shape(out: &mut String, shape: &Shape) {
+    match shape {
+        Shape::Noop | Shape::Callback(_) => {}
+        Shape::Vec(shapes) => {
+            for shape in shapes {
+                write_shape(out, shape);
+            }
+        }
+        Shape::Circle(circle) => {
+            let _ = write!(
+                out,
+                r#"<circle cx="{}" cy="{}" r="{}" {} {}/>"#,
+                circle.center.x,
+                circle.center.y,
+                circle.radius,
+                fill(circle.fill),
+                stroke(circle.stroke)
+            );
+        }
+        Shape::Ellipse(ellipse) => {
+            let _ = write!(
+                out,
+                r#"<ellipse cx="{}" cy="{}" rx="{}" ry="{}" {} {}/>"#,
+                ellipse.center.x,
+                ellipse.center.y,
+                ellipse.radius.x,
+                ellipse.radius.y,
+                fill(ellipse.fill),
+                stroke(ellipse.stroke)
+            );
+        }
+        Shape::LineSegment { points, stroke: s } => {
+            let _ = write!(
+                out,
+                r#"<line x1="{}" y1="{}" x2="{}" y2="{}" {}/>"#,
+                points[0].x,
+                points[0].y,
+                points[1].x,
+                points[1].y,
+                stroke(*s)
+            );
+        }
+        Shape::Path(path) => {
+            write_path(
+                out,
+                &path.points,
+                path.closed,
+                path.fill,
+                &path_stroke(path.stroke.width, &path.stroke.color),
+            );
+        }
+        Shape::QuadraticBezier(curve) => write_curve(
+            out,
+            'Q',
+            &curve.points,
+            curve.closed,
+            curve.fill,
+            &path_stroke(curve.stroke.width, &curve.stroke.color),
+        ),
+        Shape::CubicBezier(curve) => write_curve(
+            out,
+            'C',
+            &curve.points,
+            curve.closed,
+            curve.fill,
+            &path_stroke(curve.stroke.width, &curve.stroke.color),
+        ),
+        Shape::Rect(rect) => {
+            let r = rect.rect;
+            let _ = write!(
+                out,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{}" {} {}/>"#,
+                r.min.x,
+                r.min.y,
+                r.width(),
+                r.height(),
+                rect.corner_radius.nw,
+                fill(rect.fill),
+                stroke(rect.stroke)
+            );
+        }
+        Shape::Text(text) => write_text(out, text),
+        Shape::Mesh(mesh) => write_mesh(out, mesh),
+    }
+}
+
+fn write_path(out: &mut String, points: &[Pos2], closed: bool, color: Color32, stroke: &str) {
+    let Some((first, rest)) = points.split_first() else {
+        return;
+    };
+    let mut d = format!("M{} {}", first.x, first.y);
+    for p in rest {
+        let _ = write!(d, " L{} {}", p.x, p.y);
+    }
+    if closed {
+        d.push_str(" Z");
+    }
+    let _ = write!(out, r#"<path d="{d}" {} {stroke}/>"#, fill(color));
+}
+
+// Bezier curve through `points`, `kind` being the SVG path command for its degree.
+fn write_curve(
+    out: &mut String,
+    kind: char,
+    points: &[Pos2],
+    closed: bool,
+    color: Color32,
+    stroke: &str,
+) {
+    let Some((first, rest)) = points.split_first() else {
+        return;
+    };
+    let mut d = format!("M{} {} {kind}", first.x, first.y);
+    for p in rest {
+        let _ = write!(d, " {} {}", p.x, p.y);
+    }
+    if closed {
+        d.push_str(" Z");
+    }
+    let _ = write!(out, r#"<path d="{d}" {} {stroke}/>"#, fill(color));
+}
+
+fn write_text(out: &mut String, text: &TextShape) {
+    let Some(format) = text.galley.job.sections.first().map(|s| &s.format) else {
+        return;
+    };
+    let color = text
+        .override_text_color
+        .unwrap_or(if format.color == Color32::PLACEHOLDER {
+            text.fallback_color
+        } else {
+            format.color
+        });
+    let family = match format.font_id.family {
+        FontFamily::Monospace => "monospace",
+        _ => "sans-serif",
+    };
+
+    for placed in &text.galley.rows {
+        let Some(glyph) = placed.row.glyphs.first() else {
+            continue;
+        };
+        let x = text.pos.x + placed.pos.x + glyph.pos.x;
+        let y = text.pos.y + placed.pos.y + glyph.pos.y;
+        let _ = write!(
+            out,
+            r#"<text x="{x}" y="{y}" font-family="{family}" font-size="{}" {}"#,
+            format.font_id.size,
+            fill(color)
+        );
+        if text.angle != 0.0 {
+            let _ = write!(
+                out,
+                r#" transform="rotate({} {} {})""#,
+                text.angle.to_degrees(),
+                text.pos.x,
+                text.pos.y
+            );
+        }
+        let _ = write!(out, ">{}</text>", escape(&placed.row.text()));
+    }
+}
+
+// Untextured triangles, as the plots use for filled areas.
+fn write_mesh(out: &mut String, mesh: &Mesh) {
+    for triangle in mesh.indices.chunks_exact(3) {
+        let vertex = |i: u32| mesh.vertices.get(i as usize);
+        let (Some(a), Some(b), Some(c)) = (
+            vertex(triangle[0]),
+            vertex(triangle[1]),
+            vertex(triangle[2]),
+        ) else {
+            continue;
+        };
+        let _ = write!(
+            out,
+            r#"<polygon points="{},{} {},{} {},{}" {}/>"#,
+            a.pos.x,
+            a.pos.y,
+            b.pos.x,
+            b.pos.y,
+            c.pos.x,
+            c.pos.y,
+            fill(a.color)
+        );
+    }
+}
+
+fn fill(color: Color32) -> String {
+    if color.a() == 0 {
+        return r#"fill="none""#.to_string();
+    }
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    format!(
+        r#"fill="rgb({r},{g},{b})" fill-opacity="{:.3}""#,
+        f32::from(a) / 255.0
+    )
+}
+
+fn stroke(stroke: Stroke) -> String {
+    if stroke.is_empty() {
+        return String::new();
+    }
+    let [r, g, b, a] = stroke.color.to_srgba_unmultiplied();
+    format!(
+        r#"stroke="rgb({r},{g},{b})" stroke-opacity="{:.3}" stroke-width="{}""#,
+        f32::from(a) / 255.0,
+        stroke.width
+    )
+}
+
+fn path_stroke(width: f32, color: &ColorMode) -> String {
+    match color {
+        ColorMode::Solid(color) => stroke(Stroke::new(width, *color)),
+        // gradients can't be expressed as a single colour; the first
+        // sample is better than dropping the line
+        ColorMode::UV(f) => stroke(Stroke::new(width, f(Rect::NOTHING, Pos2::ZERO))),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+// Runtime the native save dialog runs on, as the desktop portal it talks to
+// through D-Bus needs one.
+#[cfg(not(target_arch = "wasm32"))]
+static RUNTIME: std::sync::OnceLock<tokio::runtime::Handle> = std::sync::OnceLock::new();
+
+/// Give exports the runtime to show the native save dialog on.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn set_runtime(handle: tokio::runtime::Handle) {
+    let _ = RUNTIME.set(handle);
+}
+
+/// Save `bytes` where the user picks, through a native file dialog shown off
+/// the UI thread. Whether it was saved is logged once the user picks.
+///
+/// # Errors
+///
+/// If there is no runtime to show the dialog on.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn save(file_name: &str, bytes: &[u8]) -> Result<()> {
+    let runtime = RUNTIME
+        .get()
+        .ok_or_else(|| anyhow::anyhow!("no runtime to show the save dialog on"))?;
+    let mut dialog = rfd::AsyncFileDialog::new().set_file_name(file_name);
+    if let Some(extension) = std::path::Path::new(file_name).extension() {
+        let extension = extension.to_string_lossy();
+        dialog = dialog.add_filter(extension.to_uppercase(), &[&extension]);
+    }
+    let bytes = bytes.to_vec();
+    runtime.spawn(async move {
+        let Some(file) = dialog.save_file().await else {
+            tracing::info!("Export cancelled");
+            return;
+        };
+        let path = file.path();
+        match std::fs::write(path, bytes) {
+            Ok(()) => tracing::info!("Exported {}", path.display()),
+            Err(err) => error!("Failed to export {}: {err}", path.display()),
+        }
+    });
+    Ok(())
+}
+
+// Media type of a file, from its extension.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+fn mime(file_name: &str) -> &'static str {
+    let extension = std::path::Path::new(file_name)
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("png") => "image/png",
+        Some("svg") => "image/svg+xml",
+        Some("json") => "application/json",
+        Some("toml") => "application/toml",
+        Some("jsonl") => "application/jsonl",
+        Some("log") => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Save `bytes` through a browser download.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn save(file_name: &str, bytes: &[u8]) -> Result<()> {
+    use anyhow::anyhow;
+    use eframe::wasm_bindgen::JsCast;
+
+    let js_err = |err: eframe::wasm_bindgen::JsValue| anyhow!("{err:?}");
+
+    let array = js_sys::Uint8Array::from(bytes);
+    let parts = js_sys::Array::of1(&array);
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(mime(file_name));
+    let blob =
+        web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options).map_err(js_err)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(js_err)?;
+
+    let document = web_sys::window()
+        .and_then(|w| w.document())
+        .ok_or_else(|| anyhow!("no document to download from"))?;
+    let anchor = document
+        .create_element("a")
+        .map_err(js_err)?
+        .dyn_into::<web_sys::HtmlAnchorElement>()
+        .map_err(|_| anyhow!("not an anchor element"))?;
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+
+    web_sys::Url::revoke_object_url(&url).map_err(js_err)?;
+    tracing::info!("Exported {file_name}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn media_types() {
+        assert_eq!(mime("horizon-dashboard.png"), "image/png");
+        assert_eq!(mime("horizon-art.SVG"), "image/svg+xml");
+        assert_eq!(mime("theme.toml"), "application/toml");
+        assert_eq!(mime("horizon-logs"), "application/octet-stream");
+    }
+
+    #[test]
+    fn svg_shapes() {
+        let area = Rect::from_min_max(Pos2::new(10.0, 10.0), Pos2::new(110.0, 60.0));
+        let shapes = [
+            ClippedShape {
+                clip_rect: area,
+                shape: Shape::circle_filled(Pos2::new(20.0, 20.0), 5.0, Color32::RED),
+            },
+            ClippedShape {
+                clip_rect: area,
+                shape: Shape::line_segment(
+                    [Pos2::new(10.0, 10.0), Pos2::new(50.0, 50.0)],
+                    Stroke::new(2.0, Color32::WHITE),
+                ),
+            },
+        ];
+
+        let svg = svg(&shapes, area, Color32::BLACK);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(r#"viewBox="10 10 100 50""#));
+        assert!(svg.contains(r#"<circle cx="20" cy="20" r="5" fill="rgb(255,0,0)""#));
+        assert!(svg.contains(r#"<line x1="10" y1="10" x2="50" y2="50""#));
+        // both shapes share one clip rect
+        assert_eq!(svg.matches("<clipPath").count(), 1);
+    }
+
+    #[test]
+    fn png_header() {
+        let image = ColorImage::new([2, 2], vec![Color32::RED; 4]);
+        let bytes = png(&image).unwrap();
+        assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+    }
+}
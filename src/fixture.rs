@@ -0,0 +1,35 @@
+//! Weather data shared by the tests.
+
+use lib_weather::PirateData;
+
+/// A forecast for Area 51 at noon on Sunday 19 October 2025, local time, with
+/// three hours, one day and a wind advisory.
+pub(crate) fn forecast() -> PirateData {
+    serde_json::from_str(
+        r#"{
+            "latitude": 37.233, "longitude": -115.8, "timezone": "America/Los_Angeles",
+            "offset": -7.0,
+            "currently": {"time": 1760900400, "summary": "Clear", "icon": "clear-day",
+                "temperature": 71.26, "humidity": 0.2, "windSpeed": 4.0},
+            "hourly": {"summary": "", "icon": "", "data": [
+                {"time": 1760900400, "temperature": 71.0, "icon": "clear-day"},
+                {"time": 1760904000, "temperature": 73.0, "apparentTemperature": 72.0,
+                    "summary": "Rain", "icon": "rain"},
+                {"time": 1760943600, "temperature": 55.0, "icon": "rain"}
+            ]},
+            "daily": {"summary": "", "icon": "", "data": [
+                {"time": 1760857200, "summary": "Wind, then \"calm\"", "icon": "wind",
+                    "temperatureHigh": 80.0, "temperatureLow": 52.0,
+                    "sunriseTime": 1760882460, "sunsetTime": 1760922600, "moonPhase": 0.93,
+                    "precipProbability": 0.3, "precipType": "rain",
+                    "windSpeed": 12.4, "windBearing": 225.0,
+                    "uvIndex": 5.2, "uvIndexTime": 1760904000}
+            ]},
+            "alerts": [{"title": "Wind Advisory", "regions": ["Lincoln", "Nye"],
+                "severity": "advisory", "time": 1760900000, "expires": 1760950000,
+                "description": "  Gusty winds.\n", "uri": "https://example.com"}],
+            "flags": {"units": "us", "version": "2.7", "sources": []}
+        }"#,
+    )
+    .unwrap()
+}
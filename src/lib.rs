@@ -2,13 +2,42 @@
 #![warn(clippy::all, rust_2018_idioms)]
 
 mod app;
+mod colormap;
+mod export;
+#[cfg(test)]
+mod fixture;
+mod log_file;
 mod logger;
+#[cfg(not(target_arch = "wasm32"))]
+mod metrics;
+mod model;
+mod report;
+#[cfg(not(target_arch = "wasm32"))]
+mod serve;
+mod theme;
+#[cfg(not(target_arch = "wasm32"))]
+mod tui;
 mod view;
 mod widgets;
 
 pub use app::{AppController, AppState, FetchState};
-pub use logger::{setup_logging, Logs};
-pub use view::{LogsView, WeatherView};
+pub use colormap::{ColorMap, TemperatureUnit};
+pub use export::ExportFormat;
+pub use log_file::{FileLogConfig, RollingFile, Rotation};
+pub use logger::{
+    dropped_logs, setup_logging, LogEvent, LogFilter, LogLayer, LOG_LEVELS_KEY, LOG_TARGETS,
+};
+pub use model::{
+    AlertSummary, Conditions, CurrentModel, DayForecast, HourlyTemperatures, MoonModel, Rgb,
+    SunMoonModel, SunSegment, SunTimes, TemperatureBar, TemperatureModel,
+};
+pub use report::{Report, Table};
+#[cfg(not(target_arch = "wasm32"))]
+pub use serve::{ForecastCache, Location, Server};
+pub use theme::{Sky, Theme, THEME_KEY};
+#[cfg(not(target_arch = "wasm32"))]
+pub use tui::Dashboard;
+pub use view::{ArtView, InspectorView, LogsView, ThemeEditor, WeatherView};
 pub use widgets::Widgets;
 
 pub const APP_NAME: &str = "horizon";
@@ -0,0 +1,258 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Local, NaiveDate};
+
+/// Directory to write logs to, file logging is off unless it is set.
+pub const LOG_DIR_ENV: &str = "HORIZON_LOG_DIR";
+/// `daily`, or a size such as `10MB` to start a new file at.
+pub const LOG_ROTATE_ENV: &str = "HORIZON_LOG_ROTATE";
+/// Number of rotated files to keep.
+pub const LOG_KEEP_ENV: &str = "HORIZON_LOG_KEEP";
+
+// the file being written, rotated files are stamped between the two
+const PREFIX: &str = "horizon";
+const EXTENSION: &str = "log";
+
+/// When to start a new log file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    /// At the first write of each day.
+    Daily,
+    /// Before a write would take the file over this many bytes.
+    Size(u64),
+}
+
+/// Where logs are written on disk and how long they are kept.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileLogConfig {
+    pub directory: PathBuf,
+    pub rotation: Rotation,
+    /// Rotated files kept besides the current one, older ones are deleted.
+    pub keep: usize,
+}
+
+impl FileLogConfig {
+    #[must_use]
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            rotation: Rotation::Daily,
+            keep: 7,
+        }
+    }
+
+    /// Read from `HORIZON_LOG_DIR`, `HORIZON_LOG_ROTATE` and `HORIZON_LOG_KEEP`,
+    /// or `None` if no directory is set.
+    ///
+    /// # Errors
+    ///
+    /// If the rotation or number of files to keep is invalid.
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(directory) = std::env::var(LOG_DIR_ENV) else {
+            return Ok(None);
+        };
+        let rotate = std::env::var(LOG_ROTATE_ENV).ok();
+        let keep = std::env::var(LOG_KEEP_ENV).ok();
+        Self::parse(&directory, rotate.as_deref(), keep.as_deref()).map(Some)
+    }
+
+    fn parse(directory: &str, rotate: Option<&str>, keep: Option<&str>) -> Result<Self> {
+        let mut config = Self::new(directory);
+        if let Some(rotate) = rotate {
+            config.rotation = parse_rotation(rotate)?;
+        }
+        if let Some(keep) = keep {
+            config.keep = keep
+                .trim()
+                .parse()
+                .with_context(|| format!("Invalid {LOG_KEEP_ENV}: {keep}"))?;
+        }
+        Ok(config)
+    }
+}
+
+fn parse_rotation(rotate: &str) -> Result<Rotation> {
+    let rotate = rotate.trim().to_ascii_lowercase();
+    if rotate == "daily" {
+        return Ok(Rotation::Daily);
+    }
+    let digits = rotate.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let scale = match rotate[digits.len()..].trim_end_matches('b') {
+        "" => 1,
+        "k" => 1 << 10,
+        "m" => 1 << 20,
+        "g" => 1 << 30,
+        _ => return Err(anyhow!("Invalid {LOG_ROTATE_ENV}: {rotate}")),
+    };
+    let size: u64 = digits
+        .trim()
+        .parse()
+        .with_context(|| format!("Invalid {LOG_ROTATE_ENV}: {rotate}"))?;
+    let bytes = size
+        .checked_mul(scale)
+        .ok_or_else(|| anyhow!("{LOG_ROTATE_ENV} is too large: {rotate}"))?;
+    match bytes {
+        0 => Err(anyhow!("{LOG_ROTATE_ENV} must be more than 0 bytes")),
+        bytes => Ok(Rotation::Size(bytes)),
+    }
+}
+
+/// A log file that moves aside when it's due to rotate, keeping a limited
+/// number of old files.
+pub struct RollingFile {
+    config: FileLogConfig,
+    file: File,
+    size: u64,
+    opened: NaiveDate,
+}
+
+impl RollingFile {
+    /// Open the current log file, appending to it if it exists.
+    ///
+    /// # Errors
+    ///
+    /// If the directory can't be created or the file can't be opened.
+    pub fn open(config: FileLogConfig) -> io::Result<Self> {
+        fs::create_dir_all(&config.directory)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(current_path(&config.directory))?;
+        let metadata = file.metadata()?;
+        // a file left from an earlier day rotates on the first write
+        let opened = metadata
+            .modified()
+            .map_or_else(|_| Local::now(), DateTime::<Local>::from)
+            .date_naive();
+        Ok(Self {
+            size: metadata.len(),
+            opened,
+            file,
+            config,
+        })
+    }
+
+    fn due(&self, len: usize) -> bool {
+        match self.config.rotation {
+            Rotation::Daily => Local::now().date_naive() != self.opened,
+            Rotation::Size(max) => self.size > 0 && self.size + len as u64 > max,
+        }
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let directory = &self.config.directory;
+        let stamp = Local::now().format("%Y%m%d-%H%M%S%.3f");
+        fs::rename(
+            current_path(directory),
+            directory.join(format!("{PREFIX}.{stamp}.{EXTENSION}")),
+        )?;
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(current_path(directory))?;
+        self.size = 0;
+        self.opened = Local::now().date_naive();
+        self.prune()
+    }
+
+    // Delete the oldest rotated files beyond the number to keep.
+    fn prune(&self) -> io::Result<()> {
+        let mut rotated: Vec<PathBuf> = fs::read_dir(&self.config.directory)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| is_rotated(path))
+            .collect();
+        // stamps sort in the order they were written
+        rotated.sort();
+        let excess = rotated.len().saturating_sub(self.config.keep);
+        for path in &rotated[..excess] {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+impl Write for RollingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.due(buf.len()) {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn current_path(directory: &Path) -> PathBuf {
+    directory.join(format!("{PREFIX}.{EXTENSION}"))
+}
+
+fn is_rotated(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    name.strip_prefix(&format!("{PREFIX}."))
+        .and_then(|rest| rest.strip_suffix(&format!(".{EXTENSION}")))
+        .is_some_and(|stamp| !stamp.is_empty())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_config() {
+        let config = FileLogConfig::parse("logs", Some("10MB"), Some("3")).unwrap();
+        assert_eq!(config.rotation, Rotation::Size(10 << 20));
+        assert_eq!(config.keep, 3);
+        assert_eq!(
+            FileLogConfig::parse("logs", Some("Daily"), None)
+                .unwrap()
+                .rotation,
+            Rotation::Daily
+        );
+        assert_eq!(parse_rotation("512").unwrap(), Rotation::Size(512));
+        assert!(parse_rotation("10 parsecs").is_err());
+        assert!(parse_rotation("0kb").is_err());
+        assert!(parse_rotation("99999999999g").is_err());
+        assert!(FileLogConfig::parse("logs", None, Some("-1")).is_err());
+    }
+
+    #[test]
+    fn rotates_by_size_and_keeps_the_newest() {
+        let directory =
+            std::env::temp_dir().join(format!("horizon-log-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        let mut config = FileLogConfig::new(&directory);
+        config.rotation = Rotation::Size(16);
+        config.keep = 2;
+
+        let mut file = RollingFile::open(config).unwrap();
+        for line in 0..5 {
+            file.write_all(format!("line {line} of logs\n").as_bytes())
+                .unwrap();
+            // rotated files are stamped to the millisecond
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+
+        let mut names: Vec<String> = fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(names.len(), 3);
+        assert_eq!(names[2], "horizon.log");
+        assert_eq!(
+            fs::read_to_string(directory.join("horizon.log")).unwrap(),
+            "line 4 of logs\n"
+        );
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
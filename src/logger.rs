@@ -1,99 +1,296 @@
-use std::io::{self, Write};
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Local};
 use tokio::sync::mpsc::Sender;
-use tracing::error;
-use tracing_subscriber::fmt::{self, MakeWriter};
-use tracing_subscriber::layer::SubscriberExt;
-use tracing_subscriber::EnvFilter;
+use tracing::field::{Field, Visit};
+use tracing::level_filters::LevelFilter;
+use tracing::{error, Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::{reload, EnvFilter, Registry};
 
-#[derive(Clone)]
-pub struct Logs {
-    tx: Sender<String>,
+use crate::log_file::{FileLogConfig, RollingFile};
+
+/// Key the log levels are persisted under between runs.
+pub const LOG_LEVELS_KEY: &str = "horizon_log_levels";
+
+/// Crates whose log level can be changed while running.
+pub const LOG_TARGETS: [&str; 4] = ["horizon", "lib_weather", "lib_geolocate", "lib_astro"];
+
+// events the log view's channel was too full to take
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+/// Number of events dropped because the log view's channel was full.
+#[must_use]
+pub fn dropped_logs() -> usize {
+    DROPPED.load(Ordering::Relaxed)
 }
 
-impl Write for Logs {
-    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        let s = std::str::from_utf8(buf).unwrap_or_default();
+/// A log event as the log view shows it.
+#[derive(Clone, Debug)]
+pub struct LogEvent {
+    pub time: DateTime<Local>,
+    pub level: Level,
+    /// Module the event came from.
+    pub target: String,
+    /// The message, followed by any other fields as `name=value`.
+    pub message: String,
+}
 
-        if !s.trim().is_empty() {
-            if let Err(e) = self.tx.try_send(s.to_string()) {
-                let msg = format!("Error sending log to subscriber: {e}");
-                error!(msg);
-                // we are consuming the tracing errors here so as a fallback:
-                println!("{msg}");
-            }
+impl LogEvent {
+    /// One line as the log view shows it.
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        format!(
+            "{} {:<5} {}: {}",
+            self.time.format("%Y-%m-%d %H:%M:%S%.3f"),
+            self.level.as_str(),
+            self.target,
+            self.message
+        )
+    }
+
+    /// One line of JSON, for JSON-lines exports.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::json!({
+            "time": self.time.to_rfc3339(),
+            "level": self.level.as_str(),
+            "target": self.target,
+            "message": self.message,
+        })
+        .to_string()
+    }
+}
+
+/// Sends each event to the log view, keeping its level and target apart from
+/// the message so the view can filter on them.
+pub struct LogLayer {
+    tx: Sender<LogEvent>,
+}
+
+impl LogLayer {
+    #[must_use]
+    pub fn new(tx: Sender<LogEvent>) -> Self {
+        Self { tx }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for LogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let metadata = event.metadata();
+        let log = LogEvent {
+            time: Local::now(),
+            level: *metadata.level(),
+            target: metadata.target().to_string(),
+            message: visitor.message,
+        };
+        // logging from inside the layer would come straight back here, so just count it
+        if self.tx.try_send(log).is_err() {
+            DROPPED.fetch_add(1, Ordering::Relaxed);
         }
-        Ok(buf.len())
     }
+}
 
-    fn flush(&mut self) -> io::Result<()> {
-        Ok(())
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.insert_str(0, value);
+        } else {
+            let _ = write!(self.message, " {}={value}", field.name());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message.insert_str(0, &format!("{value:?}"));
+        } else {
+            let _ = write!(self.message, " {}={value:?}", field.name());
+        }
     }
 }
 
+/// Handle to the log filter, for changing the level of each crate while running.
 #[derive(Clone)]
-pub struct LogWriter {
-    writer: Logs,
+pub struct LogFilter {
+    handle: reload::Handle<EnvFilter, Registry>,
+    levels: BTreeMap<&'static str, LevelFilter>,
+    /// directives from `RUST_LOG`, used until a level is changed here
+    env: Option<String>,
 }
 
-impl LogWriter {
-    pub fn new(tx: Sender<String>) -> Self {
+impl LogFilter {
+    fn new(handle: reload::Handle<EnvFilter, Registry>, env: Option<String>) -> Self {
         Self {
-            writer: Logs { tx },
+            handle,
+            levels: LOG_TARGETS.map(|target| (target, LevelFilter::INFO)).into(),
+            env,
         }
     }
-}
 
-impl<'a> MakeWriter<'a> for LogWriter {
-    type Writer = Logs;
+    /// Filter for the levels here, or `RUST_LOG` while it is in use.
+    fn env_filter(&self) -> EnvFilter {
+        EnvFilter::new(self.directives())
+    }
+
+    pub fn levels(&self) -> impl Iterator<Item = (&'static str, LevelFilter)> + '_ {
+        self.levels.iter().map(|(target, level)| (*target, *level))
+    }
+
+    /// `RUST_LOG` as given at startup, while it is still in use.
+    #[must_use]
+    pub fn from_env(&self) -> Option<&str> {
+        self.env.as_deref()
+    }
+
+    /// Change the level of one crate, replacing any `RUST_LOG` filter with the levels here.
+    pub fn set_level(&mut self, target: &str, level: LevelFilter) {
+        if let Some(current) = self.levels.get_mut(target) {
+            *current = level;
+            self.env = None;
+            self.reload();
+        }
+    }
+
+    /// The levels as `target=level` directives, for persisting.
+    #[must_use]
+    pub fn save(&self) -> String {
+        self.levels
+            .iter()
+            .map(|(target, level)| format!("{target}={level}"))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// Restore levels saved by `save`, unless `RUST_LOG` was given for this run.
+    pub fn restore(&mut self, saved: &str) {
+        if self.env.is_some() {
+            return;
+        }
+        for directive in saved.split(',') {
+            let Some((target, level)) = directive.split_once('=') else {
+                continue;
+            };
+            if let (Some(current), Ok(level)) = (self.levels.get_mut(target), level.parse()) {
+                *current = level;
+            }
+        }
+        self.reload();
+    }
+
+    fn directives(&self) -> String {
+        self.env.clone().unwrap_or_else(|| self.save())
+    }
 
-    fn make_writer(&'a self) -> Self::Writer {
-        self.writer.clone()
+    fn reload(&self) {
+        if let Err(e) = self.handle.reload(self.env_filter()) {
+            // the filter decides what gets logged, so this can't go through tracing
+            println!("Error changing the log filter: {e}");
+        }
     }
 }
 
+/// Send logs to the log view, and to rolling files if `file` is given.
+///
 /// # Panics
 ///
 /// Will panic if unable to setup subscriber.
-pub fn setup_logging(tx: Sender<String>) {
-    let make_writer = LogWriter::new(tx);
+#[must_use]
+pub fn setup_logging(tx: Sender<LogEvent>, file: Option<FileLogConfig>) -> LogFilter {
+    #[cfg(not(target_arch = "wasm32"))]
+    let env = std::env::var(EnvFilter::DEFAULT_ENV).ok();
+    #[cfg(target_arch = "wasm32")]
+    let env = None;
 
-    let env_filter = EnvFilter::new("horizon=info,lib_weather=info");
+    let (layer, handle) = reload::Layer::new(EnvFilter::default());
+    let filter = LogFilter::new(handle, env);
+    filter.reload();
 
-    #[cfg(not(target_arch = "wasm32"))]
-    {
-        let subscriber = tracing_subscriber::registry().with(env_filter).with(
-            fmt::layer()
-                .with_writer(make_writer)
-                .with_ansi(false)
-                .with_target(false),
-        );
-        tracing::subscriber::set_global_default(subscriber)
-            .expect("setting default subscriber failed");
+    let directory = file.as_ref().map(|config| config.directory.clone());
+    let (file, file_error) = match file.map(RollingFile::open).transpose() {
+        Ok(file) => (file, None),
+        Err(e) => (None, Some(e)),
+    };
+    let file_layer = file.map(|file| {
+        tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(Mutex::new(file))
+    });
+
+    let subscriber = tracing_subscriber::registry()
+        .with(layer)
+        .with(LogLayer::new(tx))
+        .with(file_layer);
+    tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
+
+    if let (Some(directory), Some(e)) = (directory, file_error) {
+        error!("Failed to open log file in {}: {e}", directory.display());
     }
+    filter
+}
 
-    #[cfg(target_arch = "wasm32")]
-    {
-        use tracing_subscriber::fmt::{format::Writer, time::FormatTime};
-        use web_sys::js_sys::Date;
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tokio::sync::mpsc;
 
-        #[derive(Debug)]
-        struct WasmTime;
+    #[test]
+    fn events_keep_their_fields() {
+        let (tx, mut rx) = mpsc::channel(4);
+        let subscriber = tracing_subscriber::registry().with(LogLayer::new(tx));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(target: "lib_weather", status = 503, "fetch failed");
+        });
 
-        impl FormatTime for WasmTime {
-            fn format_time(&self, w: &mut Writer<'_>) -> Result<(), std::fmt::Error> {
-                let now = Date::new_0();
-                write!(w, "[{}]", now.to_locale_time_string("en-US"))
-            }
-        }
-        let subscriber = tracing_subscriber::registry().with(env_filter).with(
-            fmt::layer()
-                .with_writer(make_writer)
-                .with_ansi(false)
-                .with_target(false)
-                .with_timer(WasmTime),
-        );
-
-        tracing::subscriber::set_global_default(subscriber)
-            .expect("setting default subscriber failed");
+        let log = rx.try_recv().unwrap();
+        assert_eq!(log.level, Level::WARN);
+        assert_eq!(log.target, "lib_weather");
+        assert_eq!(log.message, "fetch failed status=503");
+    }
+
+    #[test]
+    fn exports_one_line_per_event() {
+        let log = LogEvent {
+            time: Local::now(),
+            level: Level::ERROR,
+            target: "lib_weather".into(),
+            message: "bad \"response\"\nbody".into(),
+        };
+        assert!(log
+            .to_text()
+            .ends_with(" ERROR lib_weather: bad \"response\"\nbody"));
+
+        let json = log.to_json();
+        assert!(!json.contains('\n'));
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["level"], "ERROR");
+        assert_eq!(value["message"], log.message);
+    }
+
+    #[test]
+    fn levels_round_trip() {
+        let (_layer, handle) = reload::Layer::<EnvFilter, Registry>::new(EnvFilter::default());
+        let mut filter = LogFilter::new(handle.clone(), None);
+        filter.set_level("lib_geolocate", LevelFilter::DEBUG);
+        filter.set_level("unknown", LevelFilter::OFF);
+
+        let mut restored = LogFilter::new(handle.clone(), None);
+        restored.restore(&filter.save());
+        assert_eq!(restored.levels, filter.levels);
+
+        // RUST_LOG wins over saved levels
+        let mut from_env = LogFilter::new(handle, Some("debug".into()));
+        from_env.restore(&filter.save());
+        assert_eq!(from_env.directives(), "debug");
     }
 }
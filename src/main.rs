@@ -3,13 +3,13 @@
 
 use tokio::sync::mpsc::{self, Receiver};
 
-use horizon::{AppController, AppState};
-use lib_weather::{PirateData, PirateWeather};
+use horizon::{AppController, AppState, FileLogConfig, LogEvent, LogFilter};
+use lib_weather::{OpenMeteo, PirateData, PirateWeather};
 
-fn init_logging() -> Receiver<String> {
-    let (logtx, logrx) = mpsc::channel::<String>(100);
-    horizon::setup_logging(logtx);
-    logrx
+fn init_logging(file: Option<FileLogConfig>) -> (Receiver<LogEvent>, LogFilter) {
+    let (logtx, logrx) = mpsc::channel::<LogEvent>(100);
+    let filter = horizon::setup_logging(logtx, file);
+    (logrx, filter)
 }
 
 // native:
@@ -17,14 +17,19 @@ fn init_logging() -> Receiver<String> {
 fn main() -> eframe::Result {
     use tokio::runtime::Builder;
 
-    let logrx = init_logging();
+    let file = FileLogConfig::from_env().unwrap_or_else(|e| {
+        eprintln!("Not logging to file: {e}");
+        None
+    });
+    let (logrx, filter) = init_logging(file);
 
     let runtime = Builder::new_multi_thread()
         .enable_all()
         .build()
         .expect("Failed to build runtime");
 
-    let state = AppState::new(logrx, Some(runtime.handle()));
+    let mut state = AppState::new(logrx, Some(runtime.handle()));
+    state.logs_view.set_filter(filter);
 
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_maximized(true),
@@ -33,11 +38,13 @@ fn main() -> eframe::Result {
     eframe::run_native(
         horizon::APP_NAME,
         native_options,
-        Box::new(|_cc| {
-            Ok(Box::new(AppController::<PirateData, PirateWeather>::new(
-                state,
-                Some(runtime),
-            )))
+        Box::new(|cc| {
+            state.restore(cc.storage);
+            Ok(Box::new(AppController::<
+                PirateData,
+                PirateWeather,
+                OpenMeteo,
+            >::new(state, Some(runtime))))
         }),
     )
 }
@@ -47,8 +54,9 @@ fn main() -> eframe::Result {
 fn main() {
     use eframe::wasm_bindgen::JsCast as _;
 
-    let logrx = init_logging();
-    let state = AppState::new(logrx, None);
+    let (logrx, filter) = init_logging(None);
+    let mut state = AppState::new(logrx, None);
+    state.logs_view.set_filter(filter);
 
     let web_options = eframe::WebOptions::default();
 
@@ -68,10 +76,13 @@ fn main() {
             .start(
                 canvas,
                 web_options,
-                Box::new(|_cc| {
-                    Ok(Box::new(AppController::<PirateData, PirateWeather>::new(
-                        state, None,
-                    )))
+                Box::new(|cc| {
+                    state.restore(cc.storage);
+                    Ok(Box::new(AppController::<
+                        PirateData,
+                        PirateWeather,
+                        OpenMeteo,
+                    >::new(state, None)))
                 }),
             )
             .await;
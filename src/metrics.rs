@@ -0,0 +1,182 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+// upper bounds of the fetch latency buckets, in seconds
+const LATENCY_BUCKETS: [f64; 8] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Counts of fetches, their latency and cache use for each provider, written in
+/// the Prometheus text format.
+#[derive(Default)]
+pub struct Metrics {
+    counters: Mutex<Counters>,
+}
+
+#[derive(Default)]
+struct Counters {
+    // by provider and whether the fetch succeeded
+    fetches: BTreeMap<(&'static str, bool), u64>,
+    latency: BTreeMap<&'static str, Histogram>,
+    // by provider and whether a request found data in the cache
+    cache: BTreeMap<(&'static str, bool), u64>,
+}
+
+#[derive(Default)]
+struct Histogram {
+    // count of observations at or under each bound in `LATENCY_BUCKETS`
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Metrics {
+    pub fn record_fetch(&self, provider: &'static str, success: bool, latency: Duration) {
+        let mut counters = self.counters.lock().unwrap_or_else(PoisonError::into_inner);
+        *counters.fetches.entry((provider, success)).or_default() += 1;
+
+        let seconds = latency.as_secs_f64();
+        let histogram = counters.latency.entry(provider).or_default();
+        for (bucket, bound) in histogram.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+        histogram.sum += seconds;
+        histogram.count += 1;
+    }
+
+    pub fn record_cache(&self, provider: &'static str, hit: bool) {
+        let mut counters = self.counters.lock().unwrap_or_else(PoisonError::into_inner);
+        *counters.cache.entry((provider, hit)).or_default() += 1;
+    }
+
+    /// Append the counters and histograms to `out`.
+    pub fn write(&self, out: &mut String) {
+        let counters = self.counters.lock().unwrap_or_else(PoisonError::into_inner);
+
+        header(
+            out,
+            "horizon_fetches_total",
+            "counter",
+            "Fetches from each provider.",
+        );
+        for (&(provider, success), count) in &counters.fetches {
+            let outcome = if success { "success" } else { "failure" };
+            let _ = writeln!(
+                out,
+                "horizon_fetches_total{{provider=\"{}\",outcome=\"{outcome}\"}} {count}",
+                escape(provider)
+            );
+        }
+
+        header(
+            out,
+            "horizon_fetch_duration_seconds",
+            "histogram",
+            "Time taken by each fetch, successful or not.",
+        );
+        for (provider, histogram) in &counters.latency {
+            let provider = escape(provider);
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(histogram.buckets) {
+                let _ = writeln!(
+                    out,
+                    "horizon_fetch_duration_seconds_bucket{{provider=\"{provider}\",le=\"{bound}\"}} {count}"
+                );
+            }
+            let _ = writeln!(
+                out,
+                "horizon_fetch_duration_seconds_bucket{{provider=\"{provider}\",le=\"+Inf\"}} {}",
+                histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "horizon_fetch_duration_seconds_sum{{provider=\"{provider}\"}} {}",
+                histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "horizon_fetch_duration_seconds_count{{provider=\"{provider}\"}} {}",
+                histogram.count
+            );
+        }
+
+        header(
+            out,
+            "horizon_cache_requests_total",
+            "counter",
+            "Requests answered from the cache, or not for want of data.",
+        );
+        for (&(provider, hit), count) in &counters.cache {
+            let result = if hit { "hit" } else { "miss" };
+            let _ = writeln!(
+                out,
+                "horizon_cache_requests_total{{provider=\"{}\",result=\"{result}\"}} {count}",
+                escape(provider)
+            );
+        }
+    }
+}
+
+/// Append a gauge with a value for each location to `out`, leaving out
+/// locations without one.
+pub fn write_gauge(out: &mut String, name: &str, help: &str, values: &[(&str, Option<f64>)]) {
+    header(out, name, "gauge", help);
+    for (location, value) in values {
+        if let Some(value) = value {
+            let _ = writeln!(out, "{name}{{location=\"{}\"}} {value}", escape(location));
+        }
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+// A label value with the characters Prometheus needs escaped.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn counters_and_histograms() {
+        let metrics = Metrics::default();
+        metrics.record_fetch("Pirate", true, Duration::from_millis(200));
+        metrics.record_fetch("Pirate", false, Duration::from_secs(3));
+        metrics.record_cache("Pirate", true);
+
+        let mut out = String::new();
+        metrics.write(&mut out);
+        write_gauge(
+            &mut out,
+            "horizon_uv_index",
+            "UV index.",
+            &[("home \"1\"", Some(3.0)), ("away", None)],
+        );
+
+        for line in [
+            "horizon_fetches_total{provider=\"Pirate\",outcome=\"failure\"} 1",
+            "horizon_fetches_total{provider=\"Pirate\",outcome=\"success\"} 1",
+            // buckets count every observation at or below them
+            "horizon_fetch_duration_seconds_bucket{provider=\"Pirate\",le=\"0.1\"} 0",
+            "horizon_fetch_duration_seconds_bucket{provider=\"Pirate\",le=\"0.25\"} 1",
+            "horizon_fetch_duration_seconds_bucket{provider=\"Pirate\",le=\"5\"} 2",
+            "horizon_fetch_duration_seconds_bucket{provider=\"Pirate\",le=\"+Inf\"} 2",
+            "horizon_fetch_duration_seconds_count{provider=\"Pirate\"} 2",
+            "horizon_cache_requests_total{provider=\"Pirate\",result=\"hit\"} 1",
+            "# TYPE horizon_uv_index gauge",
+            "horizon_uv_index{location=\"home \\\"1\\\"\"} 3",
+        ] {
+            assert!(out.lines().any(|l| l == line), "missing {line} in\n{out}");
+        }
+        assert!(!out.contains("away"));
+    }
+}
@@ -0,0 +1,61 @@
+use chrono_tz::Tz;
+use lib_weather::Alert;
+
+use super::format_time;
+
+/// A weather alert, formatted for display.
+#[derive(Clone, Debug)]
+pub struct AlertSummary {
+    pub title: String,
+    pub severity: String,
+    pub regions: String,
+    /// When it ends, in local time.
+    pub expires: String,
+    pub description: String,
+    pub uri: String,
+}
+
+impl AlertSummary {
+    #[must_use]
+    pub fn new(alert: &Alert, tz: Tz) -> Self {
+        Self {
+            title: alert.title.clone(),
+            severity: alert.severity.clone(),
+            regions: alert.regions.join(", "),
+            expires: format_time(alert.expires, tz, "%a %d %b %H:%M"),
+            description: alert.description.trim().to_string(),
+            uri: alert.uri.clone(),
+        }
+    }
+
+    #[must_use]
+    pub fn all(alerts: &[Alert], tz: Tz) -> Vec<Self> {
+        alerts.iter().map(|alert| Self::new(alert, tz)).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::forecast;
+    use crate::model::timezone;
+    use lib_weather::WeatherData;
+
+    #[test]
+    fn alerts_in_local_time() {
+        let data = forecast();
+        let alerts = AlertSummary::all(data.alerts(), timezone(data.time().0));
+        assert_eq!(alerts.len(), 1);
+
+        let advisory = &alerts[0];
+        assert_eq!(advisory.title, "Wind Advisory");
+        assert_eq!(advisory.severity, "advisory");
+        assert_eq!(advisory.regions, "Lincoln, Nye");
+        // 08:46 UTC, so early on Monday in Nevada
+        assert_eq!(advisory.expires, "Mon 20 Oct 01:46");
+        assert_eq!(advisory.description, "Gusty winds.");
+
+        let utc = AlertSummary::new(&data.alerts()[0], chrono_tz::UTC);
+        assert_eq!(utc.expires, "Mon 20 Oct 08:46");
+    }
+}
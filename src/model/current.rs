@@ -0,0 +1,153 @@
+use chrono_tz::Tz;
+use lib_weather::{Condition, DataPoint, WeatherData};
+
+use super::{format_time, timezone, Rgb};
+use crate::colormap::TemperatureUnit;
+use crate::theme::Theme;
+
+/// Conditions at one time, with each value formatted for display.
+#[derive(Clone, Debug, Default)]
+pub struct Conditions {
+    pub time: i64,
+    pub condition: Condition,
+    pub summary: String,
+    /// Temperatures as given, for colouring.
+    pub temperature: Option<f64>,
+    pub apparent_temperature: Option<f64>,
+    pub current_temp: String,
+    pub feels_like: String,
+    pub precipitation_prob: String,
+    pub humidity: String,
+    pub pressure: String,
+    pub wind: String,
+    pub uv_index: String,
+    pub cloud_cover: String,
+    pub visibility: String,
+}
+
+impl Conditions {
+    #[must_use]
+    pub fn new(point: &DataPoint) -> Self {
+        Self {
+            time: point.time,
+            condition: point.condition(),
+            summary: point.summary.clone().unwrap_or_default(),
+            temperature: point.temperature,
+            apparent_temperature: point.apparent_temperature,
+            current_temp: rounded_str(point.temperature),
+            feels_like: rounded_str(point.apparent_temperature),
+            precipitation_prob: float_to_percent_str(point.precip_probability),
+            humidity: float_to_percent_str(point.humidity),
+            pressure: float_str(point.pressure),
+            wind: float_str(point.wind_speed),
+            uv_index: float_str(point.uv_index),
+            cloud_cover: float_to_percent_str(point.cloud_cover),
+            visibility: float_str(point.visibility),
+        }
+    }
+
+    /// Conditions at the forecast hour `time` if given and forecast,
+    /// otherwise the current ones.
+    #[must_use]
+    pub fn at(
+        current: Option<&DataPoint>,
+        hourly: &[DataPoint],
+        time: Option<i64>,
+    ) -> Option<Self> {
+        time.and_then(|t| hourly.iter().find(|h| h.time == t))
+            .or(current)
+            .map(Self::new)
+    }
+}
+
+/// What the current conditions widget shows.
+#[derive(Clone, Debug)]
+pub struct CurrentModel {
+    /// Which hour is shown, when it's a forecast rather than now.
+    pub heading: Option<String>,
+    pub conditions: Conditions,
+    pub temperature_color: Option<Rgb>,
+    pub feels_like_color: Option<Rgb>,
+}
+
+impl CurrentModel {
+    /// The conditions at the forecast hour `time`, or now, in Fahrenheit.
+    #[must_use]
+    pub fn new(
+        current: Option<&DataPoint>,
+        hourly: &[DataPoint],
+        tz: Tz,
+        time: Option<i64>,
+        theme: &Theme,
+    ) -> Option<Self> {
+        let conditions = Conditions::at(current, hourly, time)?;
+        let color = |t: Option<f64>| {
+            t.map(|t| Rgb::from(theme.temperature_color(t, TemperatureUnit::Fahrenheit)))
+        };
+        Some(Self {
+            heading: time.map(|t| format!("Forecast for {}", format_time(t, tz, "%a %d %b %H:%M"))),
+            temperature_color: color(conditions.temperature),
+            feels_like_color: color(conditions.apparent_temperature),
+            conditions,
+        })
+    }
+
+    #[must_use]
+    pub fn from_data(data: &dyn WeatherData, time: Option<i64>, theme: &Theme) -> Option<Self> {
+        Self::new(
+            data.current(),
+            data.hourly().map_or(&[][..], Vec::as_slice),
+            timezone(data.time().0),
+            time,
+            theme,
+        )
+    }
+}
+
+fn rounded_str(f: Option<f64>) -> String {
+    f.map_or("--".to_string(), |v| format!("{}", v.round()))
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn float_to_percent_str(f: Option<f64>) -> String {
+    f.map_or("--".to_string(), |v| {
+        format!("{}", (v * 100.0).round() as i64)
+    })
+}
+
+fn float_str(f: Option<f64>) -> String {
+    f.map_or("--".to_string(), |v| format!("{v:.1}"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::forecast;
+
+    #[test]
+    fn now_or_at_a_forecast_hour() {
+        let data = forecast();
+        let theme = Theme::dark();
+
+        let now = CurrentModel::from_data(&data, None, &theme).unwrap();
+        assert_eq!(now.heading, None);
+        assert_eq!(now.conditions.summary, "Clear");
+        assert_eq!(now.conditions.current_temp, "71");
+        assert_eq!(now.conditions.humidity, "20");
+        assert_eq!(now.conditions.pressure, "--");
+        assert_eq!(
+            now.temperature_color,
+            Some(theme.temperature_steps[3].into())
+        );
+        assert_eq!(now.feels_like_color, None);
+
+        let later = CurrentModel::from_data(&data, Some(1_760_904_000), &theme).unwrap();
+        assert_eq!(
+            later.heading.as_deref(),
+            Some("Forecast for Sun 19 Oct 13:00")
+        );
+        assert_eq!(later.conditions.condition, Condition::Rain);
+        assert_eq!(later.conditions.feels_like, "72");
+        assert!(later.feels_like_color.is_some());
+    }
+}
@@ -0,0 +1,132 @@
+use chrono_tz::Tz;
+use lib_weather::{Condition, DataPoint, Units};
+
+use super::{compass_point, format_time, local_day};
+
+/// One day of the forecast, formatted for display.
+#[derive(Clone, Debug)]
+pub struct DayForecast {
+    /// Start and end of the local day.
+    pub bounds: (i64, i64),
+    /// Weekday and day of the month.
+    pub date: String,
+    pub condition: Condition,
+    pub summary: Option<String>,
+    pub high: Option<f64>,
+    pub low: Option<f64>,
+    /// Chance and kind of precipitation, or "Dry".
+    pub precipitation: String,
+    /// Speed and compass direction.
+    pub wind: String,
+    /// Peak UV index and when it peaks.
+    pub uv: String,
+}
+
+impl DayForecast {
+    #[must_use]
+    pub fn new(day: &DataPoint, tz: Tz, units: Units) -> Self {
+        Self {
+            bounds: local_day(day.time, tz),
+            date: format_time(day.time, tz, "%a %d"),
+            condition: day.condition(),
+            summary: day.summary.clone(),
+            high: day.temperature_high,
+            low: day.temperature_low,
+            precipitation: precipitation(day),
+            wind: wind(day, units),
+            uv: uv(day, tz),
+        }
+    }
+
+    /// Every day in `days`.
+    #[must_use]
+    pub fn all(days: &[DataPoint], tz: Tz, units: Units) -> Vec<Self> {
+        days.iter().map(|day| Self::new(day, tz, units)).collect()
+    }
+}
+
+fn precipitation(day: &DataPoint) -> String {
+    match day.precip_probability {
+        Some(p) if p > 0.0 => format!(
+            "{:.0}% {}",
+            p * 100.0,
+            day.precip_type.as_deref().unwrap_or("precip")
+        ),
+        Some(_) => "Dry".to_string(),
+        None => "--".to_string(),
+    }
+}
+
+fn wind(day: &DataPoint, units: Units) -> String {
+    day.wind_speed.map_or("--".to_string(), |speed| {
+        format!(
+            "{speed:.0} {}{}",
+            units.speed(),
+            day.wind_bearing
+                .map_or(String::new(), |b| format!(" {}", compass_point(b)))
+        )
+    })
+}
+
+fn uv(day: &DataPoint, tz: Tz) -> String {
+    day.uv_index.map_or("UV --".to_string(), |index| {
+        format!(
+            "UV {index:.0}{}",
+            day.uv_index_time.map_or(String::new(), |t| format!(
+                " at {}",
+                format_time(t, tz, "%H:%M")
+            ))
+        )
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::forecast;
+    use crate::model::timezone;
+    use lib_weather::WeatherData;
+
+    #[test]
+    fn days_of_the_forecast() {
+        let data = forecast();
+        let tz = timezone(data.time().0);
+        let days = DayForecast::all(data.daily().unwrap(), tz, Units::Us);
+        assert_eq!(days.len(), 1);
+
+        let sunday = &days[0];
+        assert_eq!(sunday.bounds, (1_760_857_200, 1_760_943_600));
+        assert_eq!(sunday.date, "Sun 19");
+        assert_eq!(sunday.condition, Condition::Wind);
+        assert_eq!((sunday.high, sunday.low), (Some(80.0), Some(52.0)));
+        assert_eq!(sunday.precipitation, "30% rain");
+        assert_eq!(sunday.wind, "12 mph SW");
+        assert_eq!(sunday.uv, "UV 5 at 13:00");
+
+        let si = DayForecast::new(&data.daily().unwrap()[0], tz, Units::Si);
+        assert_eq!(si.wind, "12 m/s SW");
+    }
+
+    #[test]
+    fn missing_values() {
+        let tz = timezone("America/Los_Angeles");
+        let day = |value: serde_json::Value| {
+            DayForecast::new(&serde_json::from_value(value).unwrap(), tz, Units::Us)
+        };
+
+        let dry = day(serde_json::json!({
+            "time": 1_760_857_200, "precipProbability": 0.0, "windSpeed": 3.0, "uvIndex": 2.0
+        }));
+        assert_eq!(dry.precipitation, "Dry");
+        assert_eq!(dry.wind, "3 mph");
+        assert_eq!(dry.uv, "UV 2");
+
+        let unknown = day(serde_json::json!({
+            "time": 1_760_857_200, "precipProbability": 0.5
+        }));
+        assert_eq!(unknown.precipitation, "50% precip");
+        assert_eq!(unknown.wind, "--");
+        assert_eq!(unknown.uv, "UV --");
+        assert_eq!(day(serde_json::json!({"time": 0})).precipitation, "--");
+    }
+}
@@ -0,0 +1,166 @@
+use chrono_tz::Tz;
+use lib_weather::{Condition, DataPoint, WeatherData};
+
+use super::{format_time, in_day, timezone, DaySelection, Rgb};
+use crate::colormap::TemperatureUnit;
+use crate::theme::Theme;
+
+/// Hourly temperatures to chart, and where the conditions change along them.
+#[derive(Clone, Debug, Default)]
+pub struct HourlyTemperatures {
+    /// Time and temperature of each hour that has one.
+    pub points: Vec<(i64, f64)>,
+    /// Time of each change in conditions, starting with the first hour's.
+    pub changes: Vec<(i64, Condition)>,
+}
+
+impl HourlyTemperatures {
+    /// The hours in `day`, or all of them when there is no day selected.
+    #[must_use]
+    pub fn new(hours: &[DataPoint], day: DaySelection) -> Self {
+        let hours = hours.iter().filter(|point| in_day(day, point.time));
+        let mut temperatures = Self::default();
+        for point in hours {
+            if let Some(t) = point.temperature {
+                temperatures.points.push((point.time, t));
+            }
+            let condition = point.condition();
+            if !temperatures
+                .changes
+                .last()
+                .is_some_and(|&(_, last)| last == condition)
+            {
+                temperatures.changes.push((point.time, condition));
+            }
+        }
+        temperatures
+    }
+
+    /// Lowest and highest temperature.
+    #[must_use]
+    pub fn range(&self) -> Option<(f64, f64)> {
+        self.points.iter().fold(None, |range, &(_, t)| {
+            Some(range.map_or((t, t), |(low, high): (f64, f64)| (low.min(t), high.max(t))))
+        })
+    }
+}
+
+/// One bar of the temperature chart.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TemperatureBar {
+    pub time: i64,
+    pub temperature: f64,
+    pub color: Rgb,
+}
+
+/// What the temperature chart shows.
+#[derive(Clone, Debug, Default)]
+pub struct TemperatureModel {
+    pub bars: Vec<TemperatureBar>,
+    /// Where the conditions change, for icons along the top of the chart.
+    pub changes: Vec<(i64, Condition)>,
+    /// Forecast hour to mark on the chart.
+    pub cursor: Option<i64>,
+    pub timezone: Tz,
+}
+
+impl TemperatureModel {
+    /// The hours in `day`, or all of them, coloured by temperature in Fahrenheit.
+    #[must_use]
+    pub fn new(
+        hours: &[DataPoint],
+        tz: Tz,
+        day: DaySelection,
+        cursor: Option<i64>,
+        theme: &Theme,
+    ) -> Self {
+        let temperatures = HourlyTemperatures::new(hours, day);
+        let bars = temperatures
+            .points
+            .iter()
+            .map(|&(time, temperature)| TemperatureBar {
+                time,
+                temperature,
+                color: theme
+                    .temperature_color(temperature, TemperatureUnit::Fahrenheit)
+                    .into(),
+            })
+            .collect();
+        Self {
+            bars,
+            changes: temperatures.changes,
+            cursor,
+            timezone: tz,
+        }
+    }
+
+    #[must_use]
+    pub fn from_data(
+        data: &dyn WeatherData,
+        day: DaySelection,
+        cursor: Option<i64>,
+        theme: &Theme,
+    ) -> Self {
+        Self::new(
+            data.hourly().map_or(&[][..], Vec::as_slice),
+            timezone(data.time().0),
+            day,
+            cursor,
+            theme,
+        )
+    }
+
+    /// Local time of day at `t`, for the time axis.
+    #[must_use]
+    pub fn time_label(&self, t: i64) -> String {
+        format_time(t, self.timezone, "%H:%M")
+    }
+
+    /// Text for the point under the pointer.
+    #[must_use]
+    pub fn hover_label(&self, name: &str, t: i64, temperature: f64) -> String {
+        format!("{name}: {temperature:.1}°F at {}", self.time_label(t))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::forecast;
+    use crate::model::local_day;
+
+    #[test]
+    fn temperature_chart() {
+        let data = forecast();
+        let theme = Theme::dark();
+
+        let all = TemperatureModel::from_data(&data, None, None, &theme);
+        assert_eq!(all.bars.len(), 3);
+        assert_eq!(
+            all.changes,
+            vec![
+                (1_760_900_400, Condition::ClearDay),
+                (1_760_904_000, Condition::Rain)
+            ]
+        );
+
+        let tz = timezone("America/Los_Angeles");
+        let day = Some(local_day(1_760_900_400, tz));
+        let sunday = TemperatureModel::from_data(&data, day, Some(1_760_904_000), &theme);
+        assert_eq!(sunday.bars.len(), 2);
+        assert_eq!(
+            sunday.bars[1],
+            TemperatureBar {
+                time: 1_760_904_000,
+                temperature: 73.0,
+                color: theme.temperature_steps[3].into(),
+            }
+        );
+        assert_eq!(sunday.cursor, Some(1_760_904_000));
+        assert_eq!(sunday.time_label(1_760_904_000), "13:00");
+        assert_eq!(
+            sunday.hover_label("Temperature", 1_760_904_000, 73.0),
+            "Temperature: 73.0°F at 13:00"
+        );
+    }
+}
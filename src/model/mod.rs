@@ -0,0 +1,121 @@
+//! Weather data shaped for display, free of any UI toolkit so the egui widgets
+//! and the terminal UI show the same values, and so they can be tested without
+//! drawing anything.
+
+use chrono::{DateTime, Days, TimeZone};
+use chrono_tz::Tz;
+
+mod alerts;
+mod current;
+mod daily;
+mod hourly;
+mod sun;
+
+pub use alerts::AlertSummary;
+pub use current::{Conditions, CurrentModel};
+pub use daily::DayForecast;
+pub use hourly::{HourlyTemperatures, TemperatureBar, TemperatureModel};
+pub use sun::{
+    format_change, format_duration, MoonModel, Provided, SunMoonModel, SunSegment, SunTimes,
+};
+
+/// A colour as red, green and blue, for whichever toolkit draws it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+/// A day picked in one widget for the others to focus on, as the unix
+/// times bounding it, or `None` to show everything again.
+pub type DaySelection = Option<(i64, i64)>;
+
+/// Format a unix timestamp in the given timezone.
+#[must_use]
+pub fn format_time(timestamp: i64, tz: Tz, fmt: &str) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .map(|dt| dt.with_timezone(&tz))
+        .map_or_else(|| "--".into(), |dt| dt.format(fmt).to_string())
+}
+
+/// Whether `t` falls in the selected day, or always when there is none.
+#[must_use]
+pub fn in_day(day: DaySelection, t: i64) -> bool {
+    day.map_or(true, |(start, end)| (start..end).contains(&t))
+}
+
+/// Start and end of the local day containing `t`.
+#[must_use]
+pub fn local_day(t: i64, tz: Tz) -> (i64, i64) {
+    let midnight = |date: chrono::NaiveDate| {
+        tz.from_local_datetime(&date.and_time(chrono::NaiveTime::MIN))
+            .earliest()
+            .map_or(t, |dt| dt.timestamp())
+    };
+    let Some(date) = DateTime::from_timestamp(t, 0).map(|dt| dt.with_timezone(&tz).date_naive())
+    else {
+        return (t, t + 86400);
+    };
+    let next = date.checked_add_days(Days::new(1)).unwrap_or(date);
+    (midnight(date), midnight(next))
+}
+
+/// Eight-point compass name for a bearing in degrees.
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn compass_point(bearing: f64) -> &'static str {
+    const POINTS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+    let index = ((bearing.rem_euclid(360.0) + 22.5) / 45.0) as usize % POINTS.len();
+    POINTS[index]
+}
+
+/// The timezone named by a provider, or UTC if it isn't known.
+#[must_use]
+pub fn timezone(name: &str) -> Tz {
+    name.parse().unwrap_or(chrono_tz::UTC)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use lib_weather::DataPoint;
+
+    fn hour(time: i64, temperature: f64, icon: &str) -> DataPoint {
+        serde_json::from_value(serde_json::json!({
+            "time": time, "temperature": temperature, "icon": icon
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn hourly_temperatures_in_a_day() {
+        let tz = timezone("America/Los_Angeles");
+        let hours = [
+            hour(1_760_900_400, 71.0, "clear-day"),
+            hour(1_760_904_000, 73.0, "clear-day"),
+            hour(1_760_907_600, 68.0, "rain"),
+            // the next local day
+            hour(1_760_943_600, 55.0, "rain"),
+        ];
+        let all = HourlyTemperatures::new(&hours, None);
+        assert_eq!(all.range(), Some((55.0, 73.0)));
+        assert_eq!(all.changes.len(), 2);
+
+        let day = HourlyTemperatures::new(&hours, Some(local_day(1_760_900_400, tz)));
+        assert_eq!(day.points.len(), 3);
+        assert_eq!(day.range(), Some((68.0, 73.0)));
+    }
+
+    #[test]
+    fn conditions_at_a_forecast_hour() {
+        let now = hour(1_760_900_000, 70.4, "clear-day");
+        let hours = [hour(1_760_904_000, 73.0, "rain")];
+        let at = |time| Conditions::at(Some(&now), &hours, time).unwrap();
+        assert_eq!(at(None).current_temp, "70");
+        assert_eq!(
+            at(Some(1_760_904_000)).condition,
+            lib_weather::Condition::Rain
+        );
+        // an hour not in the forecast falls back to now
+        assert_eq!(at(Some(0)).time, now.time);
+        assert_eq!(at(None).humidity, "--");
+        assert_eq!(compass_point(350.0), "N");
+    }
+}
@@ -0,0 +1,336 @@
+use chrono_tz::Tz;
+use lib_astro::Lunation;
+use lib_weather::WeatherData;
+use serde::Serialize;
+
+use super::{format_time, local_day, timezone, Rgb};
+use crate::theme::{Sky, Theme};
+
+// sun altitude, in degrees, when its upper limb touches the horizon
+const HORIZON: f64 = -0.833;
+// seconds between samples of the sun's path
+const PATH_STEP: usize = 600;
+
+/// Values the weather provider supplied for today, preferred over the
+/// computed ones when present.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Provided {
+    pub day: Option<i64>,
+    pub sunrise: Option<i64>,
+    pub sunset: Option<i64>,
+    pub dawn: Option<i64>,
+    pub dusk: Option<i64>,
+    pub moon_phase: Option<f64>,
+}
+
+impl Provided {
+    /// What `data` gives for its first day.
+    #[must_use]
+    pub fn from_data(data: &dyn WeatherData) -> Self {
+        data.daily()
+            .and_then(|days| days.first())
+            .map_or_else(Self::default, |today| Self {
+                day: Some(today.time),
+                sunrise: today.sunrise_time,
+                sunset: today.sunset_time,
+                dawn: today.dawn_time,
+                dusk: today.dusk_time,
+                moon_phase: today.moon_phase,
+            })
+    }
+
+    /// These values if they describe the day starting `day_start`, otherwise none.
+    #[must_use]
+    pub fn for_day(self, (day_start, day_end): (i64, i64)) -> Self {
+        if self
+            .day
+            .is_some_and(|day| (day_start..day_end).contains(&day))
+        {
+            self
+        } else {
+            Self::default()
+        }
+    }
+}
+
+/// The sun's times over one local day.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct SunTimes {
+    /// Bounds of the local day.
+    pub day_start: i64,
+    pub day_end: i64,
+    pub solar_noon: i64,
+    pub sunrise: Option<i64>,
+    pub sunset: Option<i64>,
+    /// Start and end of civil twilight.
+    pub dawn: Option<i64>,
+    pub dusk: Option<i64>,
+    /// Seconds gained (or lost) over yesterday's day length.
+    pub day_length_change: Option<i64>,
+}
+
+impl SunTimes {
+    /// Times for the local day containing `at`, preferring what the provider
+    /// supplied and computing the rest from the location.
+    #[must_use]
+    pub fn new(at: i64, (lat, lon): (f64, f64), tz: Tz, provided: Provided) -> Self {
+        let (day_start, day_end) = local_day(at, tz);
+        // the provider only describes today, so forecast days are all computed
+        let provided = provided.for_day((day_start, day_end));
+
+        let computed = lib_astro::sun_times(day_start, lat, lon);
+        // compare like with like, so both lengths are computed
+        let (yesterday_start, _) = local_day(day_start - 1, tz);
+        let yesterday = lib_astro::sun_times(yesterday_start, lat, lon);
+
+        Self {
+            day_start,
+            day_end,
+            solar_noon: computed.solar_noon,
+            sunrise: provided.sunrise.or(computed.sunrise),
+            sunset: provided.sunset.or(computed.sunset),
+            dawn: provided.dawn.or(computed.civil_dawn),
+            dusk: provided.dusk.or(computed.civil_dusk),
+            day_length_change: computed
+                .day_length()
+                .zip(yesterday.day_length())
+                .map(|(today, yesterday)| today - yesterday),
+        }
+    }
+}
+
+/// A stretch of the sun's path across the day, as time and altitude at each end.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SunSegment {
+    pub from: (i64, f64),
+    pub to: (i64, f64),
+    /// Brightest by day, dimmer through twilight and dimmest at night.
+    pub color: Rgb,
+}
+
+/// The moon on the day shown.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MoonModel {
+    /// Fraction of the lunar cycle, from 0 at new moon.
+    pub phase: f64,
+    /// Labels and values to list beside it.
+    pub rows: Vec<(&'static str, String)>,
+}
+
+/// What the sun and moon widget shows.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SunMoonModel {
+    pub location: (f64, f64),
+    pub sun: SunTimes,
+    pub sun_path: Vec<SunSegment>,
+    /// Altitudes at the bottom and top of the chart, leaving room for the
+    /// twilight bands and a little sky.
+    pub altitude_range: (f64, f64),
+    /// Sunrise and sunset, with their local times.
+    pub horizon_marks: Vec<(i64, String)>,
+    /// Labels and values to list under the chart.
+    pub sun_rows: Vec<(&'static str, String)>,
+    pub moon: MoonModel,
+}
+
+impl SunMoonModel {
+    /// The local day containing `at`, preferring what the provider supplied.
+    #[must_use]
+    pub fn new(at: i64, location: (f64, f64), tz: Tz, provided: Provided, theme: &Theme) -> Self {
+        let (lat, lon) = location;
+        let sun = SunTimes::new(at, location, tz, provided);
+        let provided = provided.for_day((sun.day_start, sun.day_end));
+
+        let samples: Vec<(i64, f64)> = (sun.day_start..=sun.day_end)
+            .step_by(PATH_STEP)
+            .map(|t| (t, lib_astro::sun_position(t, lat, lon).altitude))
+            .collect();
+        let sun_path = samples
+            .windows(2)
+            .map(|pair| SunSegment {
+                from: pair[0],
+                to: pair[1],
+                color: sun_color(&theme.sky, (pair[0].1 + pair[1].1) / 2.0),
+            })
+            .collect();
+        let lowest = samples.iter().map(|&(_, alt)| alt).fold(-18.0, f64::min);
+        let highest = samples.iter().map(|&(_, alt)| alt).fold(10.0, f64::max);
+
+        let time = |t: Option<i64>| t.map_or("--".to_string(), |t| format_time(t, tz, "%H:%M"));
+        // the whole day or none of it when the sun doesn't rise or set
+        let day_length = match (sun.sunrise, sun.sunset) {
+            (Some(sunrise), Some(sunset)) => sunset - sunrise,
+            _ if samples.iter().any(|&(_, alt)| alt > HORIZON) => sun.day_end - sun.day_start,
+            _ => 0,
+        };
+        let noon = lib_astro::sun_position(sun.solar_noon, lat, lon);
+        let sun_rows = vec![
+            ("Dawn", time(sun.dawn)),
+            ("Sunrise", time(sun.sunrise)),
+            (
+                "Solar noon",
+                format!(
+                    "{} ({:.0}° high)",
+                    time(Some(sun.solar_noon)),
+                    noon.altitude
+                ),
+            ),
+            ("Sunset", time(sun.sunset)),
+            ("Dusk", time(sun.dusk)),
+            ("Day length", format_duration(day_length)),
+            (
+                "Since yesterday",
+                sun.day_length_change
+                    .map_or("--".to_string(), format_change),
+            ),
+        ];
+
+        let phase = provided
+            .moon_phase
+            .unwrap_or_else(|| lib_astro::moon_phase(at));
+        let moon_times = lib_astro::moon_times(sun.day_start, lat, lon);
+        let date = |t: i64| format_time(t, tz, "%a %d %b");
+        let moon = MoonModel {
+            phase,
+            rows: vec![
+                ("Phase", lib_astro::phase_name(phase).to_string()),
+                (
+                    "Illumination (%)",
+                    format!("{:.0}", lib_astro::illumination(phase) * 100.0),
+                ),
+                ("Moonrise", time(moon_times.rise)),
+                ("Moonset", time(moon_times.set)),
+                (
+                    "Next new moon",
+                    date(lib_astro::next_lunation(at, Lunation::New)),
+                ),
+                (
+                    "Next full moon",
+                    date(lib_astro::next_lunation(at, Lunation::Full)),
+                ),
+            ],
+        };
+
+        Self {
+            location,
+            horizon_marks: [sun.sunrise, sun.sunset]
+                .into_iter()
+                .flatten()
+                .map(|t| (t, format_time(t, tz, "%H:%M")))
+                .collect(),
+            sun,
+            sun_path,
+            altitude_range: (lowest - 2.0, highest + 5.0),
+            sun_rows,
+            moon,
+        }
+    }
+
+    /// The day containing `at` where the data was fetched.
+    #[must_use]
+    pub fn from_data(data: &dyn WeatherData, at: i64, theme: &Theme) -> Self {
+        Self::new(
+            at,
+            data.location(),
+            timezone(data.time().0),
+            Provided::from_data(data),
+            theme,
+        )
+    }
+
+    /// Position along the day, from 0 at the local midnight starting it to 1 at the next.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn day_fraction(&self, t: i64) -> f32 {
+        let length = (self.sun.day_end - self.sun.day_start).max(1);
+        ((t - self.sun.day_start) as f32 / length as f32).clamp(0.0, 1.0)
+    }
+
+    /// The sun's altitude at `t`, if that's during the day shown.
+    #[must_use]
+    pub fn sun_altitude(&self, t: i64) -> Option<f64> {
+        let (lat, lon) = self.location;
+        (self.sun.day_start..self.sun.day_end)
+            .contains(&t)
+            .then(|| lib_astro::sun_position(t, lat, lon).altitude)
+    }
+}
+
+// Colour of the sun's path at a given altitude.
+fn sun_color(sky: &Sky, altitude: f64) -> Rgb {
+    let color = if altitude >= HORIZON {
+        sky.sun
+    } else if altitude >= -6.0 {
+        sky.sun_twilight
+    } else if altitude >= -18.0 {
+        sky.sun_dark_twilight
+    } else {
+        sky.sun_night
+    };
+    color.into()
+}
+
+/// A length of time as hours and minutes.
+#[must_use]
+pub fn format_duration(seconds: i64) -> String {
+    format!("{}h {:02}m", seconds / 3600, (seconds % 3600) / 60)
+}
+
+/// A change in length of time as signed minutes and seconds.
+#[must_use]
+pub fn format_change(seconds: i64) -> String {
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let seconds = seconds.abs();
+    format!("{sign}{}m {:02}s", seconds / 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::forecast;
+
+    #[test]
+    fn sun_and_moon_for_today() {
+        let theme = Theme::dark();
+        let model = SunMoonModel::from_data(&forecast(), 1_760_900_400, &theme);
+
+        // the provider's sunrise and sunset, the rest computed
+        assert_eq!(model.sun.sunrise, Some(1_760_882_460));
+        assert_eq!(model.sun_rows[1], ("Sunrise", "07:01".to_string()));
+        assert_eq!(model.sun_rows[3], ("Sunset", "18:10".to_string()));
+        assert_eq!(model.sun_rows[5], ("Day length", "11h 09m".to_string()));
+        assert!(model.sun.dawn.is_some_and(|dawn| dawn < 1_760_882_460));
+        assert_eq!(
+            model.horizon_marks,
+            vec![
+                (1_760_882_460, "07:01".to_string()),
+                (1_760_922_600, "18:10".to_string())
+            ]
+        );
+
+        // a sample every ten minutes, bright by day and dark at night
+        assert_eq!(model.sun_path.len(), 144);
+        assert_eq!(model.sun_path[0].color, theme.sky.sun_night.into());
+        assert_eq!(model.sun_path[72].color, theme.sky.sun.into());
+        assert!(model.altitude_range.0 <= -20.0);
+
+        assert!((model.day_fraction(model.sun.solar_noon) - 0.5).abs() < 0.05);
+        assert!(model
+            .sun_altitude(1_760_900_400)
+            .is_some_and(|alt| alt > 30.0));
+        assert_eq!(model.sun_altitude(model.sun.day_end), None);
+
+        assert!((model.moon.phase - 0.93).abs() < f64::EPSILON);
+        assert_eq!(model.moon.rows[0], ("Phase", "Waning Crescent".to_string()));
+    }
+
+    #[test]
+    fn forecast_days_are_computed() {
+        let theme = Theme::dark();
+        // Monday, so the provider's Sunday values don't apply
+        let model = SunMoonModel::from_data(&forecast(), 1_760_990_400, &theme);
+        assert_ne!(model.sun.sunrise, Some(1_760_882_460));
+        assert!(model.sun.sunrise.is_some());
+        assert!((model.moon.phase - 0.93).abs() > f64::EPSILON);
+    }
+}
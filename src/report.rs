@@ -0,0 +1,293 @@
+use std::fmt::Write;
+
+use chrono::DateTime;
+use chrono_tz::Tz;
+use lib_weather::{DataPoint, Units, WeatherData};
+use serde_json::{Map, Value};
+
+/// What a report covers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Report {
+    /// Current conditions.
+    Now,
+    /// Each hour, for up to this many days.
+    Hourly(usize),
+    /// Each day, for up to this many days.
+    Daily(usize),
+}
+
+/// Rows of weather values, printable as an aligned table, CSV or JSON.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Table {
+    /// Key of each column, as used in CSV headers and JSON.
+    pub keys: Vec<&'static str>,
+    /// Title of each column for the aligned table, with its unit.
+    pub titles: Vec<String>,
+    /// Values of each row, starting with its time in RFC 3339.
+    pub rows: Vec<Vec<Value>>,
+    // time of each row in the aligned table, shorter for reading
+    labels: Vec<String>,
+}
+
+// a column, and the value for it from a data point
+type Column = (&'static str, String, fn(&DataPoint) -> Option<f64>);
+
+impl Table {
+    /// The report from `data`, given in `units`.
+    #[must_use]
+    pub fn new(data: &dyn WeatherData, report: Report, units: Units) -> Self {
+        let tz: Tz = data.time().0.parse().unwrap_or(chrono_tz::UTC);
+        let (points, time_format): (Vec<&DataPoint>, &str) = match report {
+            Report::Now => (data.current().into_iter().collect(), "%F %H:%M"),
+            Report::Hourly(days) => (
+                data.hourly()
+                    .map(|h| h.iter().take(days * 24).collect())
+                    .unwrap_or_default(),
+                "%a %H:%M",
+            ),
+            Report::Daily(days) => (
+                data.daily()
+                    .map(|d| d.iter().take(days).collect())
+                    .unwrap_or_default(),
+                "%a %F",
+            ),
+        };
+        let columns = columns(report, units);
+        let time = |t: i64| DateTime::from_timestamp(t, 0).map(|t| t.with_timezone(&tz));
+        let labels = points
+            .iter()
+            .map(|point| {
+                time(point.time).map_or("--".to_string(), |t| t.format(time_format).to_string())
+            })
+            .collect();
+
+        let mut keys = vec!["time", "summary"];
+        let mut titles = vec!["Time".to_string(), "Summary".to_string()];
+        if let Report::Daily(_) = report {
+            keys.extend(["sunrise", "sunset"]);
+            titles.extend(["Sunrise".to_string(), "Sunset".to_string()]);
+        }
+        keys.extend(columns.iter().map(|(key, _, _)| *key));
+        titles.extend(columns.iter().map(|(_, title, _)| title.clone()));
+
+        let rows = points
+            .into_iter()
+            .map(|point| {
+                let mut row = vec![
+                    time(point.time).map_or(Value::Null, |t| Value::from(t.to_rfc3339())),
+                    point.summary.clone().map_or(Value::Null, Value::from),
+                ];
+                if let Report::Daily(_) = report {
+                    let clock = |t: Option<i64>| {
+                        t.and_then(|t| DateTime::from_timestamp(t, 0))
+                            .map_or(Value::Null, |t| {
+                                Value::from(t.with_timezone(&tz).format("%H:%M").to_string())
+                            })
+                    };
+                    row.push(clock(point.sunrise_time));
+                    row.push(clock(point.sunset_time));
+                }
+                row.extend(columns.iter().map(|(_, _, value)| number(value(point))));
+                row
+            })
+            .collect();
+
+        Self {
+            keys,
+            titles,
+            rows,
+            labels,
+        }
+    }
+
+    /// Columns lined up under their titles, with `--` for missing values.
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let cells: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .zip(&self.labels)
+            .map(|(row, label)| {
+                std::iter::once(label.clone())
+                    .chain(row.iter().skip(1).map(text))
+                    .collect()
+            })
+            .collect();
+        let widths: Vec<usize> = self
+            .titles
+            .iter()
+            .enumerate()
+            .map(|(i, title)| {
+                cells
+                    .iter()
+                    .map(|row| row[i].chars().count())
+                    .chain(std::iter::once(title.chars().count()))
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        let mut out = String::new();
+        for line in std::iter::once(&self.titles).chain(&cells) {
+            let padded: Vec<String> = line
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| {
+                    let pad = width - cell.chars().count();
+                    format!("{cell}{}", " ".repeat(pad))
+                })
+                .collect();
+            let _ = writeln!(out, "{}", padded.join("  ").trim_end());
+        }
+        out
+    }
+
+    /// A header of keys, then a line per row, quoting text as needed.
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        let mut out = self.keys.join(",");
+        out.push('\n');
+        for row in &self.rows {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|value| match value {
+                    Value::Null => String::new(),
+                    Value::String(s) if s.contains([',', '"', '\n']) => {
+                        format!("\"{}\"", s.replace('"', "\"\""))
+                    }
+                    other => text(other),
+                })
+                .collect();
+            out.push_str(&cells.join(","));
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Each row as an object keyed by column.
+    #[must_use]
+    pub fn to_json(&self) -> Vec<Value> {
+        self.rows
+            .iter()
+            .map(|row| {
+                let object: Map<String, Value> = self
+                    .keys
+                    .iter()
+                    .map(|key| (*key).to_string())
+                    .zip(row.iter().cloned())
+                    .collect();
+                Value::Object(object)
+            })
+            .collect()
+    }
+}
+
+fn columns(report: Report, units: Units) -> Vec<Column> {
+    let temp = units.temperature();
+    let speed = units.speed();
+    let mut columns: Vec<Column> = match report {
+        Report::Daily(_) => vec![
+            ("high", format!("High ({temp})"), |p| p.temperature_high),
+            ("low", format!("Low ({temp})"), |p| p.temperature_low),
+        ],
+        Report::Now | Report::Hourly(_) => vec![
+            ("temperature", format!("Temp ({temp})"), |p| p.temperature),
+            ("feels_like", format!("Feels ({temp})"), |p| {
+                p.apparent_temperature
+            }),
+        ],
+    };
+    columns.extend([
+        (
+            "precip_probability",
+            "Precip (%)".into(),
+            |p: &DataPoint| percent(p.precip_probability),
+        ),
+        ("humidity", "Humidity (%)".into(), |p| percent(p.humidity)),
+        ("wind_speed", format!("Wind ({speed})"), |p| p.wind_speed),
+        ("wind_gust", format!("Gust ({speed})"), |p| p.wind_gust),
+        ("wind_bearing", "Bearing (°)".into(), |p| p.wind_bearing),
+        ("uv_index", "UV".into(), |p| p.uv_index),
+    ] as [Column; 6]);
+    if report == Report::Now {
+        columns.extend([
+            ("pressure", "Pressure (hPa)".into(), |p: &DataPoint| {
+                p.pressure
+            }),
+            (
+                "visibility",
+                format!("Visibility ({})", units.distance()),
+                |p| p.visibility,
+            ),
+            ("cloud_cover", "Cloud (%)".into(), |p| {
+                percent(p.cloud_cover)
+            }),
+        ] as [Column; 3]);
+    }
+    columns
+}
+
+// A fraction from 0 to 1 as a percentage.
+fn percent(value: Option<f64>) -> Option<f64> {
+    value.map(|v| (v * 100.0).round())
+}
+
+// Rounded to a tenth, as providers give more precision than is meaningful.
+fn number(value: Option<f64>) -> Value {
+    value
+        .map(|v| (v * 10.0).round() / 10.0)
+        .and_then(serde_json::Number::from_f64)
+        .map_or(Value::Null, Value::Number)
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::Null => "--".to_string(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::forecast;
+
+    #[test]
+    fn current_conditions() {
+        let table = Table::new(&forecast(), Report::Now, Units::Us);
+        let now = &table.to_json()[0];
+        assert_eq!(now["time"], "2025-10-19T12:00:00-07:00");
+        assert_eq!(now["temperature"], 71.3);
+        assert_eq!(now["humidity"], 20.0);
+        assert_eq!(now["pressure"], Value::Null);
+        let text = table.to_text();
+        let row = text.lines().nth(1).unwrap();
+        assert!(row.starts_with("2025-10-19 12:00  Clear"));
+        assert!(row.contains("  --"));
+    }
+
+    #[test]
+    fn forecasts() {
+        let data = forecast();
+        let hourly = Table::new(&data, Report::Hourly(1), Units::Us);
+        assert_eq!(hourly.rows.len(), 3);
+        // machine output keeps the date, the table only the weekday
+        assert_eq!(hourly.to_json()[1]["time"], "2025-10-19T13:00:00-07:00");
+        assert!(hourly.to_csv().contains("\n2025-10-19T13:00:00-07:00,"));
+        assert!(hourly.to_text().contains("\nSun 13:00  "));
+
+        let daily = Table::new(&data, Report::Daily(3), Units::Si);
+        assert!(daily.titles.contains(&"High (°C)".to_string()));
+        let csv = daily.to_csv();
+        let mut lines = csv.lines();
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with("time,summary,sunrise,sunset,high,low"));
+        assert_eq!(
+            lines.next().unwrap(),
+            "2025-10-19T00:00:00-07:00,\"Wind, then \"\"calm\"\"\",07:01,18:10,80.0,52.0,30.0,,12.4,,225.0,5.2"
+        );
+    }
+}
@@ -0,0 +1,705 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::str::FromStr;
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use axum::extract::{Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use lib_weather::{
+    AirQualityData, AirQualityFetch, AqiScale, DataPoint, WeatherData, WeatherFetch,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tracing::{info, warn};
+
+use crate::metrics::{write_gauge, Metrics};
+use crate::model::{timezone, Provided, SunTimes};
+
+/// A named place to keep a forecast for, given as `name=lat,lon`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Location {
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl FromStr for Location {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, coordinates) = s
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Expected name=lat,lon, got {s}"))?;
+        let (lat, lon) = coordinates
+            .split_once(',')
+            .ok_or_else(|| anyhow!("Expected name=lat,lon, got {s}"))?;
+        let parse = |value: &str| {
+            value
+                .trim()
+                .parse::<f64>()
+                .with_context(|| format!("Invalid coordinate in {s}: {value}"))
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(anyhow!("Missing name in {s}"));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            lat: parse(lat)?,
+            lon: parse(lon)?,
+        })
+    }
+}
+
+/// The latest forecast for each location, written by the scheduled refresh and
+/// read by every request, so clients never reach the provider themselves.
+pub struct ForecastCache<D> {
+    entries: RwLock<HashMap<String, Entry<D>>>,
+}
+
+struct Entry<D> {
+    data: Option<Arc<D>>,
+    // unix time of the last successful fetch
+    fetched_at: Option<i64>,
+    // why the last fetch failed, cleared by the next success
+    error: Option<String>,
+}
+
+impl<D> Default for ForecastCache<D> {
+    fn default() -> Self {
+        Self {
+            entries: RwLock::default(),
+        }
+    }
+}
+
+impl<D> ForecastCache<D> {
+    /// Keep `result` for `location`, holding on to the last good forecast if it failed.
+    pub fn store(&self, location: &str, result: Result<D>) {
+        let mut entries = self.entries.write().unwrap_or_else(PoisonError::into_inner);
+        let entry = entries.entry(location.to_string()).or_insert(Entry {
+            data: None,
+            fetched_at: None,
+            error: None,
+        });
+        match result {
+            Ok(data) => {
+                entry.data = Some(Arc::new(data));
+                entry.fetched_at = Some(chrono::Utc::now().timestamp());
+                entry.error = None;
+            }
+            Err(e) => entry.error = Some(format!("{e:#}")),
+        }
+    }
+
+    /// The forecast for `location` and when it was fetched, or why there is none.
+    ///
+    /// # Errors
+    ///
+    /// If no fetch for `location` has succeeded yet.
+    pub fn get(&self, location: &str) -> Result<(Arc<D>, i64)> {
+        let entries = self.entries.read().unwrap_or_else(PoisonError::into_inner);
+        let entry = entries
+            .get(location)
+            .ok_or_else(|| anyhow!("No forecast fetched yet"))?;
+        match (&entry.data, entry.fetched_at) {
+            (Some(data), Some(fetched_at)) => Ok((Arc::clone(data), fetched_at)),
+            _ => Err(anyhow!(entry
+                .error
+                .clone()
+                .unwrap_or_else(|| "No forecast fetched yet".to_string()))),
+        }
+    }
+}
+
+/// Serves the forecasts for a set of locations over HTTP as JSON, fetching
+/// them from `F`, and air quality from `Q`, on a schedule.
+pub struct Server<D, F, Q>
+where
+    D: WeatherData + Send + Sync + 'static,
+    F: WeatherFetch<Output = D>,
+    Q: AirQualityFetch,
+    Q::Output: AirQualityData + Send + Sync + 'static,
+{
+    shared: Arc<Shared<D, Q::Output>>,
+    refresh: Duration,
+    // whether to serve `/metrics`
+    metrics: bool,
+    _fetcher: PhantomData<F>,
+}
+
+struct Shared<D, A> {
+    locations: Vec<Location>,
+    cache: ForecastCache<D>,
+    air_quality: ForecastCache<A>,
+    // names of the providers, to label their metrics
+    weather_provider: &'static str,
+    air_provider: &'static str,
+    metrics: Metrics,
+}
+
+#[derive(Deserialize)]
+struct LocationQuery {
+    location: Option<String>,
+}
+
+/// The conditions at one time as served, whichever provider they came from.
+/// `WeatherFetch` gives US units, so each is named with its unit.
+#[derive(Serialize)]
+struct Point {
+    time: i64,
+    summary: Option<String>,
+    condition: &'static str,
+    temperature_f: Option<f64>,
+    feels_like_f: Option<f64>,
+    dew_point_f: Option<f64>,
+    humidity_percent: Option<f64>,
+    pressure_hpa: Option<f64>,
+    wind_speed_mph: Option<f64>,
+    wind_gust_mph: Option<f64>,
+    wind_bearing_deg: Option<f64>,
+    precip_probability_percent: Option<f64>,
+    precip_intensity_in_per_hr: Option<f64>,
+    precip_type: Option<String>,
+    cloud_cover_percent: Option<f64>,
+    uv_index: Option<f64>,
+    visibility_mi: Option<f64>,
+}
+
+impl From<&DataPoint> for Point {
+    fn from(point: &DataPoint) -> Self {
+        Self {
+            time: point.time,
+            summary: point.summary.clone(),
+            condition: point.condition().label(),
+            temperature_f: point.temperature,
+            feels_like_f: point.apparent_temperature,
+            dew_point_f: point.dew_point,
+            humidity_percent: percent(point.humidity),
+            pressure_hpa: point.pressure,
+            wind_speed_mph: point.wind_speed,
+            wind_gust_mph: point.wind_gust,
+            wind_bearing_deg: point.wind_bearing,
+            precip_probability_percent: percent(point.precip_probability),
+            precip_intensity_in_per_hr: point.precip_intensity,
+            precip_type: point.precip_type.clone(),
+            cloud_cover_percent: percent(point.cloud_cover),
+            uv_index: point.uv_index,
+            visibility_mi: point.visibility,
+        }
+    }
+}
+
+/// One day of the forecast as served, in the same units as `Point`.
+#[derive(Serialize)]
+struct Day {
+    /// Unix time of the local midnight starting the day.
+    time: i64,
+    summary: Option<String>,
+    condition: &'static str,
+    high_f: Option<f64>,
+    low_f: Option<f64>,
+    precip_probability_percent: Option<f64>,
+    precip_type: Option<String>,
+    wind_speed_mph: Option<f64>,
+    wind_bearing_deg: Option<f64>,
+    uv_index: Option<f64>,
+    uv_index_time: Option<i64>,
+    sunrise: Option<i64>,
+    sunset: Option<i64>,
+}
+
+impl From<&DataPoint> for Day {
+    fn from(day: &DataPoint) -> Self {
+        Self {
+            time: day.time,
+            summary: day.summary.clone(),
+            condition: day.condition().label(),
+            high_f: day.temperature_high,
+            low_f: day.temperature_low,
+            precip_probability_percent: percent(day.precip_probability),
+            precip_type: day.precip_type.clone(),
+            wind_speed_mph: day.wind_speed,
+            wind_bearing_deg: day.wind_bearing,
+            uv_index: day.uv_index,
+            uv_index_time: day.uv_index_time,
+            sunrise: day.sunrise_time,
+            sunset: day.sunset_time,
+        }
+    }
+}
+
+// A fraction from 0 to 1 as a percentage.
+fn percent(value: Option<f64>) -> Option<f64> {
+    value.map(|v| v * 100.0)
+}
+
+impl<D, F, Q> Server<D, F, Q>
+where
+    D: WeatherData + Send + Sync + 'static,
+    F: WeatherFetch<Output = D>,
+    Q: AirQualityFetch,
+    Q::Output: AirQualityData + Send + Sync + 'static,
+{
+    /// # Errors
+    ///
+    /// If `locations` is empty or names a place twice, as each name has one
+    /// cache entry.
+    pub fn new(locations: Vec<Location>, refresh: Duration) -> Result<Self> {
+        if locations.is_empty() {
+            return Err(anyhow!("Serving needs at least one location"));
+        }
+        for (i, location) in locations.iter().enumerate() {
+            if locations[..i].iter().any(|l| l.name == location.name) {
+                return Err(anyhow!(
+                    "Location {} is given more than once",
+                    location.name
+                ));
+            }
+        }
+        Ok(Self {
+            shared: Arc::new(Shared {
+                locations,
+                cache: ForecastCache::default(),
+                air_quality: ForecastCache::default(),
+                weather_provider: provider_name::<F>(),
+                air_provider: provider_name::<Q>(),
+                metrics: Metrics::default(),
+            }),
+            refresh,
+            metrics: false,
+            _fetcher: PhantomData,
+        })
+    }
+
+    /// Also serve `/metrics` for Prometheus, with the latest values for each
+    /// location and the health of each provider.
+    #[must_use]
+    pub fn with_metrics(mut self) -> Self {
+        self.metrics = true;
+        self
+    }
+
+    /// Fetch every location once.
+    pub async fn refresh(&self) {
+        refresh_all::<D, F, Q>(&self.shared).await;
+    }
+
+    /// The endpoints, each taking an optional `?location=name` and defaulting
+    /// to the first location.
+    pub fn router(&self) -> Router {
+        let mut router = Router::new()
+            .route("/locations", get(locations::<D, Q::Output>))
+            .route("/current", get(current::<D, Q::Output>))
+            .route("/hourly", get(hourly::<D, Q::Output>))
+            .route("/daily", get(daily::<D, Q::Output>))
+            .route("/alerts", get(alerts::<D, Q::Output>))
+            .route("/sun", get(sun::<D, Q::Output>));
+        if self.metrics {
+            router = router.route("/metrics", get(metrics::<D, Q::Output>));
+        }
+        router.with_state(Arc::clone(&self.shared))
+    }
+
+    /// Serve on `listener` until the process ends, refreshing in the background.
+    ///
+    /// # Errors
+    ///
+    /// If the server stops accepting connections.
+    pub async fn run(self, listener: TcpListener) -> Result<()> {
+        let shared = Arc::clone(&self.shared);
+        let refresh = self.refresh;
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(refresh);
+            loop {
+                interval.tick().await;
+                refresh_all::<D, F, Q>(&shared).await;
+            }
+        });
+
+        info!("Serving on {}", listener.local_addr()?);
+        axum::serve(listener, self.router())
+            .await
+            .context("Server stopped")
+    }
+}
+
+async fn refresh_all<D, F, Q>(shared: &Shared<D, Q::Output>)
+where
+    F: WeatherFetch<Output = D>,
+    Q: AirQualityFetch,
+{
+    for location in &shared.locations {
+        let started = Instant::now();
+        let result = F::fetch_weather(location.lat, location.lon).await;
+        let (provider, success) = (shared.weather_provider, result.is_ok());
+        shared
+            .metrics
+            .record_fetch(provider, success, started.elapsed());
+        match &result {
+            Ok(_) => info!("Refreshed the forecast for {}", location.name),
+            Err(e) => warn!(
+                "Failed to refresh the forecast for {}: {e:#}",
+                location.name
+            ),
+        }
+        shared.cache.store(&location.name, result);
+
+        let started = Instant::now();
+        let result = Q::fetch_air_quality(location.lat, location.lon).await;
+        let (provider, success) = (shared.air_provider, result.is_ok());
+        shared
+            .metrics
+            .record_fetch(provider, success, started.elapsed());
+        if let Err(e) = &result {
+            warn!("Failed to refresh air quality for {}: {e:#}", location.name);
+        }
+        shared.air_quality.store(&location.name, result);
+    }
+}
+
+// The type's own name, without its path, to tell providers apart.
+fn provider_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+// The forecast for the location asked for, wrapped with where and when it's
+// from, or an error response.
+fn respond<D, A>(
+    shared: &Shared<D, A>,
+    query: &LocationQuery,
+    body: impl FnOnce(&D) -> Value,
+) -> Response {
+    let location = match &query.location {
+        Some(name) => shared.locations.iter().find(|l| &l.name == name),
+        None => shared.locations.first(),
+    };
+    let Some(location) = location else {
+        return error(StatusCode::NOT_FOUND, "Unknown location");
+    };
+    let cached = shared.cache.get(&location.name);
+    shared
+        .metrics
+        .record_cache(shared.weather_provider, cached.is_ok());
+    match cached {
+        Ok((data, fetched_at)) => Json(json!({
+            "location": location,
+            "fetched_at": fetched_at,
+            "data": body(&data),
+        }))
+        .into_response(),
+        Err(e) => error(StatusCode::SERVICE_UNAVAILABLE, &format!("{e:#}")),
+    }
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Json(json!({ "error": message }))).into_response()
+}
+
+fn to_value(value: impl Serialize) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+async fn locations<D, A>(State(shared): State<Arc<Shared<D, A>>>) -> Json<Vec<Location>> {
+    Json(shared.locations.clone())
+}
+
+async fn current<D: WeatherData, A>(
+    State(shared): State<Arc<Shared<D, A>>>,
+    Query(query): Query<LocationQuery>,
+) -> Response {
+    respond(&shared, &query, |data| {
+        to_value(data.current().map(Point::from))
+    })
+}
+
+async fn hourly<D: WeatherData, A>(
+    State(shared): State<Arc<Shared<D, A>>>,
+    Query(query): Query<LocationQuery>,
+) -> Response {
+    respond(&shared, &query, |data| {
+        to_value(data.hourly().map_or_else(Vec::new, |hours| {
+            hours.iter().map(Point::from).collect::<Vec<_>>()
+        }))
+    })
+}
+
+async fn daily<D: WeatherData, A>(
+    State(shared): State<Arc<Shared<D, A>>>,
+    Query(query): Query<LocationQuery>,
+) -> Response {
+    respond(&shared, &query, |data| {
+        to_value(data.daily().map_or_else(Vec::new, |days| {
+            days.iter().map(Day::from).collect::<Vec<_>>()
+        }))
+    })
+}
+
+async fn alerts<D: WeatherData, A>(
+    State(shared): State<Arc<Shared<D, A>>>,
+    Query(query): Query<LocationQuery>,
+) -> Response {
+    respond(&shared, &query, |data| to_value(data.alerts()))
+}
+
+// Today's sun times, preferring the provider's as the widget does.
+async fn sun<D: WeatherData, A>(
+    State(shared): State<Arc<Shared<D, A>>>,
+    Query(query): Query<LocationQuery>,
+) -> Response {
+    respond(&shared, &query, |data| {
+        let sun = SunTimes::new(
+            chrono::Utc::now().timestamp(),
+            data.location(),
+            timezone(data.time().0),
+            Provided::from_data(data),
+        );
+        to_value(sun)
+    })
+}
+
+// The latest values for each location, then the provider metrics.
+async fn metrics<D: WeatherData, A: AirQualityData>(
+    State(shared): State<Arc<Shared<D, A>>>,
+) -> impl IntoResponse {
+    let current: Vec<_> = shared
+        .locations
+        .iter()
+        .map(|location| {
+            let data = shared.cache.get(&location.name).ok();
+            (location.name.as_str(), data)
+        })
+        .collect();
+    let gauge = |out: &mut String, name, help, value: fn(&DataPoint) -> Option<f64>| {
+        let values: Vec<(&str, Option<f64>)> = current
+            .iter()
+            .map(|(location, data)| {
+                let point = data.as_ref().and_then(|(data, _)| data.current());
+                (*location, point.and_then(value))
+            })
+            .collect();
+        write_gauge(out, name, help, &values);
+    };
+
+    let mut out = String::new();
+    gauge(
+        &mut out,
+        "horizon_temperature_fahrenheit",
+        "Latest temperature.",
+        |p| p.temperature,
+    );
+    gauge(
+        &mut out,
+        "horizon_humidity_ratio",
+        "Latest relative humidity, from 0 to 1.",
+        |p| p.humidity,
+    );
+    gauge(
+        &mut out,
+        "horizon_pressure_hpa",
+        "Latest sea-level air pressure.",
+        |p| p.pressure,
+    );
+    gauge(
+        &mut out,
+        "horizon_wind_speed_mph",
+        "Latest wind speed.",
+        |p| p.wind_speed,
+    );
+    gauge(
+        &mut out,
+        "horizon_precipitation_probability_ratio",
+        "Latest chance of precipitation, from 0 to 1.",
+        |p| p.precip_probability,
+    );
+    gauge(&mut out, "horizon_uv_index", "Latest UV index.", |p| {
+        p.uv_index
+    });
+
+    let aqi: Vec<(&str, Option<f64>)> = shared
+        .locations
+        .iter()
+        .map(|location| {
+            let cached = shared.air_quality.get(&location.name);
+            shared
+                .metrics
+                .record_cache(shared.air_provider, cached.is_ok());
+            let value = cached.ok().and_then(|(data, _)| {
+                data.current()
+                    .and_then(|point| point.aqi(AqiScale::UsEpa))
+                    .map(|aqi| aqi.value)
+            });
+            (location.name.as_str(), value)
+        })
+        .collect();
+    write_gauge(
+        &mut out,
+        "horizon_air_quality_index",
+        "Latest US EPA air quality index.",
+        &aqi,
+    );
+
+    shared.metrics.write(&mut out);
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixture::forecast;
+
+    use lib_weather::{AirQualityPoint, Concentrations, OpenMeteoAirData, PirateData};
+    use tokio::runtime::Runtime;
+
+    // Succeeds anywhere but the north pole.
+    struct MockProvider {}
+
+    #[async_trait::async_trait]
+    impl WeatherFetch for MockProvider {
+        type Output = PirateData;
+
+        async fn fetch_weather(lat: f64, lon: f64) -> Result<Self::Output> {
+            if lat >= 90.0 {
+                return Err(anyhow!("No forecast at the pole"));
+            }
+            Ok(PirateData {
+                latitude: lat,
+                longitude: lon,
+                ..forecast()
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl AirQualityFetch for MockProvider {
+        type Output = OpenMeteoAirData;
+
+        async fn fetch_air_quality(_lat: f64, _lon: f64) -> Result<Self::Output> {
+            Ok(OpenMeteoAirData {
+                current: Some(AirQualityPoint {
+                    time: 1_760_900_400,
+                    concentrations: Concentrations {
+                        pm2_5: Some(9.0),
+                        ..Concentrations::default()
+                    },
+                }),
+                ..OpenMeteoAirData::default()
+            })
+        }
+    }
+
+    #[test]
+    fn locations_from_arguments() {
+        assert_eq!(
+            "home=37.2, -115.8".parse::<Location>().unwrap(),
+            Location {
+                name: "home".to_string(),
+                lat: 37.2,
+                lon: -115.8
+            }
+        );
+        assert!("37.2,-115.8".parse::<Location>().is_err());
+        assert!("home=north,-115.8".parse::<Location>().is_err());
+    }
+
+    #[test]
+    fn names_are_unique() {
+        let server = |locations: &[&str]| {
+            Server::<PirateData, MockProvider, MockProvider>::new(
+                locations.iter().map(|l| l.parse().unwrap()).collect(),
+                Duration::from_secs(3600),
+            )
+        };
+        assert!(server(&["home=1,2", "away=3,4"]).is_ok());
+        assert!(server(&["home=1,2", "home=3,4"]).is_err());
+        assert!(server(&[]).is_err());
+    }
+
+    #[test]
+    fn serves_from_the_cache() {
+        let runtime = Runtime::new().unwrap();
+        runtime.block_on(async {
+            let server = Server::<PirateData, MockProvider, MockProvider>::new(
+                vec![
+                    "area51=37.2,-115.8".parse().unwrap(),
+                    "pole=90,0".parse().unwrap(),
+                ],
+                Duration::from_secs(3600),
+            )
+            .unwrap()
+            .with_metrics();
+            server.refresh().await;
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let router = server.router();
+            tokio::spawn(async move { axum::serve(listener, router).await });
+
+            let get_text = |path: &str| {
+                let url = format!("{url}{path}");
+                async move {
+                    let response = reqwest::get(url).await.unwrap();
+                    (response.status(), response.text().await.unwrap())
+                }
+            };
+            let get = |path: &str| {
+                let response = get_text(path);
+                async move {
+                    let (status, text) = response.await;
+                    (status, serde_json::from_str::<Value>(&text).unwrap())
+                }
+            };
+
+            let (status, current) = get("/current").await;
+            assert_eq!(status, 200);
+            assert_eq!(current["location"]["name"], "area51");
+            assert_eq!(current["data"]["temperature_f"], 71.26);
+            assert_eq!(current["data"]["humidity_percent"], 20.0);
+            assert_eq!(current["data"]["condition"], "Clear");
+            // the provider's own field names stay out of the response
+            assert!(current["data"].get("temperature").is_none());
+
+            let (_, hourly) = get("/hourly").await;
+            assert_eq!(hourly["data"][1]["feels_like_f"], 72.0);
+            let (_, daily) = get("/daily").await;
+            assert_eq!(daily["data"][0]["high_f"], 80.0);
+            assert_eq!(daily["data"][0]["wind_speed_mph"], 12.4);
+
+            let (_, alerts) = get("/alerts?location=area51").await;
+            assert_eq!(alerts["data"][0]["title"], "Wind Advisory");
+            let (_, sun) = get("/sun").await;
+            assert!(sun["data"]["sunrise"].is_i64());
+
+            let (status, pole) = get("/daily?location=pole").await;
+            assert_eq!(status, 503);
+            assert_eq!(pole["error"], "No forecast at the pole");
+            let (status, _) = get("/hourly?location=nowhere").await;
+            assert_eq!(status, 404);
+
+            let (status, metrics) = get_text("/metrics").await;
+            assert_eq!(status, 200);
+            for line in [
+                "horizon_temperature_fahrenheit{location=\"area51\"} 71.26",
+                "horizon_air_quality_index{location=\"area51\"} 50",
+                // every request was answered without asking the provider again
+                "horizon_fetches_total{provider=\"MockProvider\",outcome=\"success\"} 3",
+                "horizon_fetches_total{provider=\"MockProvider\",outcome=\"failure\"} 1",
+                // five requests, then air quality for both locations
+                "horizon_cache_requests_total{provider=\"MockProvider\",result=\"hit\"} 7",
+                "horizon_cache_requests_total{provider=\"MockProvider\",result=\"miss\"} 1",
+            ] {
+                assert!(
+                    metrics.lines().any(|l| l == line),
+                    "missing {line} in\n{metrics}"
+                );
+            }
+            assert!(!metrics.contains("horizon_temperature_fahrenheit{location=\"pole\"}"));
+        });
+    }
+}
@@ -0,0 +1,303 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use egui::{Color32, Context, FontFamily, FontId, Id, Style, TextStyle};
+use serde::{Deserialize, Serialize};
+
+use crate::colormap::{ColorMap, TemperatureUnit};
+use crate::model::Rgb;
+
+/// Key the theme is persisted under between runs.
+pub const THEME_KEY: &str = "horizon_theme";
+
+// text size egui's default style is laid out around
+const DEFAULT_TEXT_SIZE: f32 = 12.5;
+
+/// Colours and fonts used across the app, editable in the theme editor and
+/// shareable as JSON or TOML.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub name: String,
+    pub dark_mode: bool,
+    /// Selection and highlight colour.
+    pub accent: Color32,
+    /// Size of body text, other text is scaled with it.
+    pub text_size: f32,
+    /// Set all text in the monospace font rather than the proportional one.
+    pub monospace: bool,
+    /// Status dots for a location found or weather fetched.
+    pub status_ok: Color32,
+    /// Scale every temperature is coloured with, or `None` for the steps below.
+    pub temperature_scale: Option<ColorMap>,
+    /// Fills for freezing, cold, cool, pleasant, warm, hot and very hot.
+    pub temperature_steps: [Color32; 7],
+    pub sky: Sky,
+}
+
+/// Colours of the sun and moon widget.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Sky {
+    pub night: Color32,
+    pub astronomical_twilight: Color32,
+    pub nautical_twilight: Color32,
+    pub civil_twilight: Color32,
+    pub day: Color32,
+    /// Shading over the times of civil twilight.
+    pub twilight_shade: Color32,
+    pub horizon: Color32,
+    pub labels: Color32,
+    /// The sun's path by day, in twilight, in deep twilight and at night.
+    pub sun: Color32,
+    pub sun_twilight: Color32,
+    pub sun_dark_twilight: Color32,
+    pub sun_night: Color32,
+}
+
+impl From<Color32> for Rgb {
+    fn from(color: Color32) -> Self {
+        Rgb(color.r(), color.g(), color.b())
+    }
+}
+
+impl From<Rgb> for Color32 {
+    fn from(Rgb(r, g, b): Rgb) -> Self {
+        Color32::from_rgb(r, g, b)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Default for Sky {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    #[must_use]
+    pub fn dark() -> Self {
+        Self {
+            name: "Dark".into(),
+            dark_mode: true,
+            accent: Color32::from_rgb(0, 92, 128),
+            text_size: DEFAULT_TEXT_SIZE,
+            monospace: false,
+            status_ok: Color32::LIGHT_GREEN,
+            temperature_scale: None,
+            temperature_steps: [
+                Color32::from_rgb(135, 206, 250),
+                Color32::from_rgb(173, 216, 230),
+                Color32::from_rgb(255, 255, 153),
+                Color32::from_rgb(255, 215, 0),
+                Color32::from_rgb(255, 165, 0),
+                Color32::from_rgb(255, 99, 71),
+                Color32::from_rgb(178, 34, 34),
+            ],
+            sky: Sky::dark(),
+        }
+    }
+
+    #[must_use]
+    pub fn light() -> Self {
+        Self {
+            name: "Light".into(),
+            dark_mode: false,
+            accent: Color32::from_rgb(144, 209, 255),
+            text_size: DEFAULT_TEXT_SIZE,
+            monospace: false,
+            status_ok: Color32::from_rgb(0, 150, 60),
+            temperature_scale: None,
+            // deeper than the dark theme's, to stand out on a light background
+            temperature_steps: [
+                Color32::from_rgb(30, 110, 200),
+                Color32::from_rgb(70, 150, 210),
+                Color32::from_rgb(200, 180, 30),
+                Color32::from_rgb(230, 160, 0),
+                Color32::from_rgb(230, 110, 0),
+                Color32::from_rgb(220, 60, 40),
+                Color32::from_rgb(150, 20, 20),
+            ],
+            sky: Sky::light(),
+        }
+    }
+
+    /// Presets to start a theme from.
+    #[must_use]
+    pub fn presets() -> [Theme; 2] {
+        [Theme::dark(), Theme::light()]
+    }
+
+    /// The theme last applied to `ctx`.
+    #[must_use]
+    pub fn current(ctx: &Context) -> Arc<Theme> {
+        ctx.data(|d| d.get_temp(Id::new(THEME_KEY)))
+            .unwrap_or_default()
+    }
+
+    /// Make this the theme for `ctx`, including egui's own visuals and text sizes.
+    pub fn apply(&self, ctx: &Context) {
+        ctx.set_theme(if self.dark_mode {
+            egui::Theme::Dark
+        } else {
+            egui::Theme::Light
+        });
+
+        let scale = self.text_size / DEFAULT_TEXT_SIZE;
+        let text_styles = Style::default().text_styles;
+        ctx.style_mut(|style| {
+            style.visuals.selection.bg_fill = self.accent;
+            style.text_styles = text_styles
+                .into_iter()
+                .map(|(text_style, font): (TextStyle, FontId)| {
+                    let family = if self.monospace {
+                        FontFamily::Monospace
+                    } else {
+                        font.family
+                    };
+                    (text_style, FontId::new(font.size * scale, family))
+                })
+                .collect();
+        });
+
+        ctx.data_mut(|d| d.insert_temp(Id::new(THEME_KEY), Arc::new(self.clone())));
+    }
+
+    /// Colour for a temperature, the same wherever it is shown.
+    #[must_use]
+    pub fn temperature_color(&self, temp: f64, unit: TemperatureUnit) -> Color32 {
+        const STEPS: [f64; 6] = [32.0, 50.0, 65.0, 75.0, 85.0, 95.0];
+        if let Some(map) = self.temperature_scale {
+            return map.color(temp, unit);
+        }
+        let fahrenheit = unit.to_fahrenheit(temp);
+        let index = STEPS.iter().take_while(|&&step| fahrenheit >= step).count();
+        self.temperature_steps[index]
+    }
+
+    /// # Errors
+    ///
+    /// If the theme can't be serialized.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// # Errors
+    ///
+    /// If the theme can't be serialized.
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+
+    /// Read a theme shared as JSON or TOML.
+    ///
+    /// # Errors
+    ///
+    /// If the text is neither a valid JSON nor TOML theme.
+    pub fn parse(text: &str) -> Result<Self> {
+        if text.trim_start().starts_with('{') {
+            serde_json::from_str(text).map_err(|err| anyhow!("Invalid JSON theme: {err}"))
+        } else {
+            toml::from_str(text).map_err(|err| anyhow!("Invalid TOML theme: {err}"))
+        }
+    }
+}
+
+impl Sky {
+    fn dark() -> Self {
+        Self {
+            night: Color32::from_rgb(10, 10, 30),
+            astronomical_twilight: Color32::from_rgb(16, 18, 50),
+            nautical_twilight: Color32::from_rgb(24, 30, 72),
+            civil_twilight: Color32::from_rgb(40, 44, 96),
+            day: Color32::from_rgb(36, 70, 120),
+            twilight_shade: Color32::from_rgba_unmultiplied(230, 120, 60, 40),
+            horizon: Color32::BLACK,
+            labels: Color32::LIGHT_GRAY,
+            sun: Color32::YELLOW,
+            sun_twilight: Color32::from_rgb(230, 120, 60),
+            sun_dark_twilight: Color32::from_rgb(90, 110, 200),
+            sun_night: Color32::from_rgb(20, 24, 82),
+        }
+    }
+
+    fn light() -> Self {
+        Self {
+            night: Color32::from_rgb(70, 80, 120),
+            astronomical_twilight: Color32::from_rgb(100, 115, 160),
+            nautical_twilight: Color32::from_rgb(135, 155, 200),
+            civil_twilight: Color32::from_rgb(175, 195, 230),
+            day: Color32::from_rgb(200, 225, 250),
+            twilight_shade: Color32::from_rgba_unmultiplied(240, 140, 60, 50),
+            horizon: Color32::from_gray(60),
+            labels: Color32::from_gray(30),
+            sun: Color32::from_rgb(230, 170, 0),
+            sun_twilight: Color32::from_rgb(220, 100, 40),
+            sun_dark_twilight: Color32::from_rgb(60, 80, 170),
+            sun_night: Color32::from_rgb(20, 24, 82),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        let theme = Theme::light();
+        assert_eq!(Theme::parse(&theme.to_json().unwrap()).unwrap(), theme);
+        assert_eq!(Theme::parse(&theme.to_toml().unwrap()).unwrap(), theme);
+    }
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        let theme = Theme::parse("name = \"House\"\ntext_size = 14.0\n").unwrap();
+        assert_eq!(theme.name, "House");
+        assert_eq!(theme.sky, Sky::default());
+        assert!(!theme.monospace);
+    }
+
+    #[test]
+    fn applies_fonts() {
+        let ctx = Context::default();
+        let theme = Theme {
+            text_size: DEFAULT_TEXT_SIZE * 2.0,
+            monospace: true,
+            ..Theme::dark()
+        };
+        theme.apply(&ctx);
+        let body = ctx.style().text_styles[&TextStyle::Body].clone();
+        assert_eq!(body.family, FontFamily::Monospace);
+        assert!(
+            (body.size - Style::default().text_styles[&TextStyle::Body].size * 2.0).abs() < 0.01
+        );
+    }
+
+    #[test]
+    fn temperature_colors() {
+        let mut theme = Theme::dark();
+        let f = TemperatureUnit::Fahrenheit;
+        assert_eq!(theme.temperature_color(20.0, f), theme.temperature_steps[0]);
+        assert_eq!(theme.temperature_color(70.0, f), theme.temperature_steps[3]);
+        assert_eq!(
+            theme.temperature_color(110.0, f),
+            theme.temperature_steps[6]
+        );
+        assert_eq!(
+            theme.temperature_color(0.0, TemperatureUnit::Celsius),
+            theme.temperature_steps[1]
+        );
+
+        theme.temperature_scale = Some(ColorMap::Cividis);
+        assert_eq!(
+            theme.temperature_color(70.0, f),
+            ColorMap::Cividis.color(70.0, f)
+        );
+    }
+}
@@ -0,0 +1,555 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use lib_weather::{PirateData, PirateWeather, Units, WeatherData};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph, Row, Sparkline, Table, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use tokio::runtime::Runtime;
+
+use crate::colormap::TemperatureUnit;
+use crate::model::{
+    format_change, format_duration, format_time, timezone, AlertSummary, Conditions, DayForecast,
+    HourlyTemperatures, Provided, Rgb, SunTimes,
+};
+use crate::theme::Theme;
+
+// how long to wait for a key before checking for new data
+const TICK: Duration = Duration::from_millis(250);
+
+/// The weather at one location as a dashboard in the terminal, showing the
+/// same values as the widgets.
+pub struct Dashboard {
+    location: (f64, f64),
+    units: Units,
+    // how often to fetch the forecast again
+    refresh: Duration,
+    data: Option<PirateData>,
+    error: Option<String>,
+    fetching: bool,
+    fetched_at: Option<Instant>,
+    // index into the hourly forecast, or `None` for now
+    hour: Option<usize>,
+    // index into the daily forecast, or `None` for every hour
+    day: Option<usize>,
+    // colours temperatures the same way as the widgets
+    theme: Theme,
+    quit: bool,
+    tx: Sender<Result<PirateData>>,
+    rx: Receiver<Result<PirateData>>,
+}
+
+impl Dashboard {
+    #[must_use]
+    pub fn new(location: (f64, f64), units: Units, refresh: Duration) -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self {
+            location,
+            units,
+            refresh,
+            data: None,
+            error: None,
+            fetching: false,
+            fetched_at: None,
+            hour: None,
+            day: None,
+            theme: Theme::dark(),
+            quit: false,
+            tx,
+            rx,
+        }
+    }
+
+    /// Colour temperatures as `theme` does, rather than as the dark theme.
+    #[must_use]
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Take over the terminal until the user quits, fetching in the background.
+    ///
+    /// # Errors
+    ///
+    /// If the runtime can't be built or the terminal can't be drawn to.
+    pub fn run(mut self) -> Result<()> {
+        let runtime = Runtime::new().context("Failed to build runtime")?;
+        let mut terminal = ratatui::init();
+        let result = self.event_loop(&mut terminal, &runtime);
+        ratatui::restore();
+        result
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal, runtime: &Runtime) -> Result<()> {
+        while !self.quit {
+            if self
+                .fetched_at
+                .map_or(true, |fetched| fetched.elapsed() >= self.refresh)
+            {
+                self.fetch(runtime);
+            }
+            for result in self.rx.try_iter().collect::<Vec<_>>() {
+                self.receive(result);
+            }
+
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if event::poll(TICK)? {
+                if let Event::Key(key) = event::read()? {
+                    self.handle_key(key);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn fetch(&mut self, runtime: &Runtime) {
+        if self.fetching {
+            return;
+        }
+        self.fetching = true;
+        // count from the attempt, so a failing provider isn't retried every tick
+        self.fetched_at = Some(Instant::now());
+        let tx = self.tx.clone();
+        let (lat, lon) = self.location;
+        let units = self.units;
+        runtime.spawn(async move {
+            let _ = tx.send(PirateWeather::fetch_forecast(lat, lon, units).await);
+        });
+    }
+
+    fn receive(&mut self, result: Result<PirateData>) {
+        self.fetching = false;
+        match result {
+            Ok(data) => {
+                // the forecast has moved on, so the old indices point at other hours
+                self.hour = None;
+                self.day = self
+                    .day
+                    .filter(|&day| day < data.daily().map_or(0, Vec::len));
+                self.data = Some(data);
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("{e:#}")),
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        if key.kind != KeyEventKind::Press {
+            return;
+        }
+        let hours = self
+            .data
+            .as_ref()
+            .and_then(|d| d.hourly())
+            .map_or(0, Vec::len);
+        let days = self
+            .data
+            .as_ref()
+            .and_then(|d| d.daily())
+            .map_or(0, Vec::len);
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('r') => self.fetched_at = None,
+            KeyCode::Right if hours > 0 => {
+                self.hour = Some(self.hour.map_or(0, |h| (h + 1).min(hours - 1)));
+            }
+            KeyCode::Left => self.hour = self.hour.and_then(|h| h.checked_sub(1)),
+            KeyCode::Home => {
+                self.hour = None;
+                self.day = None;
+            }
+            KeyCode::Down if days > 0 => {
+                self.day = Some(self.day.map_or(0, |d| (d + 1).min(days - 1)));
+            }
+            KeyCode::Up => self.day = self.day.and_then(|d| d.checked_sub(1)),
+            _ => {}
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame<'_>) {
+        let [header, body, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        frame.render_widget(self.header(), header);
+        frame.render_widget(
+            Line::from("q quit  r refresh  ←/→ hour  Home now  ↑/↓ day").dark_gray(),
+            footer,
+        );
+
+        let Some(data) = &self.data else {
+            let message = if self.error.is_some() {
+                "No forecast yet."
+            } else {
+                "Fetching the forecast…"
+            };
+            frame.render_widget(Paragraph::new(message), body);
+            return;
+        };
+
+        let alerts = AlertSummary::all(data.alerts(), timezone(data.time().0));
+        let [top, chart, bottom] = Layout::vertical([
+            Constraint::Length(12),
+            Constraint::Length(7),
+            Constraint::Min(0),
+        ])
+        .areas(body);
+        let [current, sun] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(top);
+        let [daily, alerts_area] = if alerts.is_empty() {
+            Layout::horizontal([Constraint::Percentage(100), Constraint::Length(0)]).areas(bottom)
+        } else {
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .areas(bottom)
+        };
+
+        self.current(frame, current, data);
+        self.sun(frame, sun, data);
+        self.hourly(frame, chart, data);
+        self.daily(frame, daily, data);
+        if !alerts.is_empty() {
+            alerts_panel(frame, alerts_area, &alerts);
+        }
+    }
+
+    fn header(&self) -> Line<'_> {
+        let (lat, lon) = self.location;
+        let mut spans = vec![
+            Span::from("horizon").bold(),
+            Span::from(format!("  {lat:.3}, {lon:.3}")),
+        ];
+        if let Some(data) = &self.data {
+            let tz = timezone(data.time().0);
+            if let Some(now) = data.current() {
+                spans.push(Span::from(format!(
+                    "  updated {}",
+                    format_time(now.time, tz, "%H:%M")
+                )));
+            }
+        }
+        if self.fetching {
+            spans.push(Span::from("  refreshing…").dark_gray());
+        }
+        if let Some(error) = &self.error {
+            spans.push(Span::from(format!("  {error}")).red());
+        }
+        Line::from(spans)
+    }
+
+    // The forecast hour picked, if any.
+    fn selected_time(&self, data: &PirateData) -> Option<i64> {
+        let hourly = data.hourly()?;
+        self.hour
+            .and_then(|h| hourly.get(h))
+            .map(|point| point.time)
+    }
+
+    fn current(&self, frame: &mut Frame<'_>, area: Rect, data: &PirateData) {
+        let tz = timezone(data.time().0);
+        let time = self.selected_time(data);
+        let hourly = data.hourly().map_or(&[][..], Vec::as_slice);
+        let title = time.map_or_else(
+            || " Now ".to_string(),
+            |t| format!(" Forecast for {} ", format_time(t, tz, "%a %d %b %H:%M")),
+        );
+        let block = Block::bordered().title(title);
+        let Some(conditions) = Conditions::at(data.current(), hourly, time) else {
+            frame.render_widget(Paragraph::new("No current conditions.").block(block), area);
+            return;
+        };
+
+        let (speed, distance) = (self.units.speed(), self.units.distance());
+        let row = |label: &str, value: String| {
+            Line::from(vec![
+                Span::from(format!("{label:<14}")).dark_gray(),
+                value.into(),
+            ])
+        };
+        let lines = vec![
+            Line::from(format!(
+                "{}  {}",
+                conditions.condition.label(),
+                conditions.summary
+            ))
+            .bold(),
+            Line::default(),
+            Line::from(vec![
+                Span::from(format!("{:<14}", "Temperature")).dark_gray(),
+                self.temperature(conditions.temperature, &conditions.current_temp),
+            ]),
+            Line::from(vec![
+                Span::from(format!("{:<14}", "Feels like")).dark_gray(),
+                self.temperature(conditions.apparent_temperature, &conditions.feels_like),
+            ]),
+            row(
+                "Precipitation",
+                format!("{}%", conditions.precipitation_prob),
+            ),
+            row("Humidity", format!("{}%", conditions.humidity)),
+            row("Pressure", format!("{} hPa", conditions.pressure)),
+            row("Wind", format!("{} {speed}", conditions.wind)),
+            row(
+                "UV index",
+                format!(
+                    "{}   Cloud {}%",
+                    conditions.uv_index, conditions.cloud_cover
+                ),
+            ),
+            row(
+                "Visibility",
+                format!("{} {distance}", conditions.visibility),
+            ),
+        ];
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn temperature(&self, value: Option<f64>, text: &str) -> Span<'static> {
+        let span = Span::from(format!("{text}{}", self.units.temperature()));
+        match value {
+            Some(t) => span.fg(self.color(t)),
+            None => span,
+        }
+    }
+
+    // Same colours as the widgets, from the dark theme unless given another.
+    fn color(&self, temperature: f64) -> Color {
+        let unit = match self.units {
+            Units::Us => TemperatureUnit::Fahrenheit,
+            Units::Si | Units::Ca | Units::Uk => TemperatureUnit::Celsius,
+        };
+        let Rgb(r, g, b) = self.theme.temperature_color(temperature, unit).into();
+        Color::Rgb(r, g, b)
+    }
+
+    fn sun(&self, frame: &mut Frame<'_>, area: Rect, data: &PirateData) {
+        let tz = timezone(data.time().0);
+        let at = self
+            .selected_time(data)
+            .or_else(|| self.selected_day(data).map(|day| day.bounds.0))
+            .unwrap_or_else(|| chrono::Utc::now().timestamp());
+        let sun = SunTimes::new(at, data.location(), tz, Provided::from_data(data));
+
+        let time = |t: Option<i64>| t.map_or("--".to_string(), |t| format_time(t, tz, "%H:%M"));
+        let day_length = match (sun.sunrise, sun.sunset) {
+            (Some(sunrise), Some(sunset)) => format_duration(sunset - sunrise),
+            _ => "--".to_string(),
+        };
+        let rows = [
+            ("Dawn", time(sun.dawn)),
+            ("Sunrise", time(sun.sunrise)),
+            ("Solar noon", time(Some(sun.solar_noon))),
+            ("Sunset", time(sun.sunset)),
+            ("Dusk", time(sun.dusk)),
+            ("Daylight", day_length),
+            (
+                "Change",
+                sun.day_length_change
+                    .map_or("--".to_string(), format_change),
+            ),
+        ];
+        let lines: Vec<Line<'_>> = rows
+            .into_iter()
+            .map(|(label, value)| {
+                Line::from(vec![
+                    Span::from(format!("{label:<12}")).dark_gray(),
+                    value.into(),
+                ])
+            })
+            .collect();
+        let title = format!(" Sun {} ", format_time(sun.day_start, tz, "%a %d %b"));
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(title)),
+            area,
+        );
+    }
+
+    fn selected_day(&self, data: &PirateData) -> Option<DayForecast> {
+        let day = data.daily()?.get(self.day?)?;
+        Some(DayForecast::new(day, timezone(data.time().0), self.units))
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn hourly(&self, frame: &mut Frame<'_>, area: Rect, data: &PirateData) {
+        let hourly = data.hourly().map_or(&[][..], Vec::as_slice);
+        let day = self.selected_day(data);
+        let temperatures = HourlyTemperatures::new(hourly, day.as_ref().map(|d| d.bounds));
+        let unit = self.units.temperature();
+        let title = match (&day, temperatures.range()) {
+            (Some(day), Some((low, high))) => {
+                format!(" Hourly {}  {low:.0}{unit} to {high:.0}{unit} ", day.date)
+            }
+            (None, Some((low, high))) => format!(" Hourly  {low:.0}{unit} to {high:.0}{unit} "),
+            (_, None) => " Hourly ".to_string(),
+        };
+        let block = Block::bordered().title(title);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let Some((low, high)) = temperatures.range() else {
+            frame.render_widget(Paragraph::new("No hourly forecast."), inner);
+            return;
+        };
+        let [chart, marker] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(inner);
+
+        // lift the coldest hour off the baseline so it still shows
+        let values: Vec<u64> = temperatures
+            .points
+            .iter()
+            .map(|&(_, t)| (t - low).round() as u64 + 1)
+            .collect();
+        frame.render_widget(
+            Sparkline::default()
+                .data(&values)
+                .max((high - low).round() as u64 + 1)
+                .style(Style::default().fg(self.color((low + high) / 2.0))),
+            chart,
+        );
+
+        // point at the picked hour under its column
+        let picked = self
+            .selected_time(data)
+            .and_then(|t| temperatures.points.iter().position(|&(time, _)| time == t));
+        if let Some(column) = picked.filter(|&c| c < usize::from(marker.width)) {
+            let tz = timezone(data.time().0);
+            let label = format!(
+                "^ {}",
+                format_time(temperatures.points[column].0, tz, "%H:%M")
+            );
+            frame.render_widget(Line::from(format!("{}{label}", " ".repeat(column))), marker);
+        }
+    }
+
+    fn daily(&self, frame: &mut Frame<'_>, area: Rect, data: &PirateData) {
+        let tz = timezone(data.time().0);
+        let days = DayForecast::all(data.daily().map_or(&[][..], Vec::as_slice), tz, self.units);
+        let rows = days.iter().enumerate().map(|(i, day)| {
+            let temperature = |value: Option<f64>| {
+                value.map_or(Span::from("--"), |t| {
+                    Span::from(format!("{t:.0}°")).fg(self.color(t))
+                })
+            };
+            let row = Row::new(vec![
+                Line::from(day.date.clone()),
+                Line::from(day.condition.label()),
+                Line::from(vec![
+                    temperature(day.high),
+                    " / ".into(),
+                    temperature(day.low),
+                ]),
+                Line::from(day.precipitation.clone()),
+                Line::from(day.wind.clone()),
+                Line::from(day.uv.clone()),
+            ]);
+            if self.day == Some(i) {
+                row.add_modifier(Modifier::REVERSED)
+            } else {
+                row
+            }
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(7),
+                Constraint::Length(18),
+                Constraint::Length(10),
+                Constraint::Length(12),
+                Constraint::Length(12),
+                Constraint::Min(10),
+            ],
+        )
+        .header(Row::new(["Day", "Conditions", "High/Low", "Precip", "Wind", "UV"]).bold())
+        .block(Block::bordered().title(" Daily "));
+        frame.render_widget(table, area);
+    }
+}
+
+fn alerts_panel(frame: &mut Frame<'_>, area: Rect, alerts: &[AlertSummary]) {
+    let mut lines = Vec::new();
+    for alert in alerts {
+        lines.push(
+            Line::from(format!("{}: {}", alert.severity, alert.title))
+                .red()
+                .bold(),
+        );
+        lines.push(Line::from(format!("Until {}, {}", alert.expires, alert.regions)).dark_gray());
+        lines.push(Line::from(alert.description.clone()));
+        lines.push(Line::default());
+    }
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .block(Block::bordered().title(" Alerts ")),
+        area,
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::colormap::ColorMap;
+    use crate::fixture::forecast;
+    use ratatui::backend::TestBackend;
+    use ratatui::crossterm::event::KeyModifiers;
+
+    fn press(dashboard: &mut Dashboard, code: KeyCode) {
+        dashboard.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn keys_move_the_selection() {
+        let mut dashboard = Dashboard::new((37.2, -115.8), Units::Us, Duration::from_secs(600));
+        dashboard.receive(Ok(forecast()));
+
+        press(&mut dashboard, KeyCode::Left);
+        assert_eq!(dashboard.hour, None);
+        for _ in 0..4 {
+            press(&mut dashboard, KeyCode::Right);
+        }
+        assert_eq!(dashboard.hour, Some(2));
+        press(&mut dashboard, KeyCode::Down);
+        press(&mut dashboard, KeyCode::Down);
+        assert_eq!(dashboard.day, Some(0));
+
+        let mut terminal = ratatui::Terminal::new(TestBackend::new(100, 40)).unwrap();
+        terminal.draw(|frame| dashboard.draw(frame)).unwrap();
+        let screen = format!("{:?}", terminal.backend().buffer());
+        assert!(screen.contains("Forecast for Mon 20 Oct 00:00"));
+
+        press(&mut dashboard, KeyCode::Home);
+        assert_eq!((dashboard.hour, dashboard.day), (None, None));
+        press(&mut dashboard, KeyCode::Char('q'));
+        assert!(dashboard.quit);
+    }
+
+    #[test]
+    fn colours_follow_the_theme() {
+        let dashboard = Dashboard::new((37.2, -115.8), Units::Si, Duration::from_secs(600));
+        let Rgb(r, g, b) = Theme::dark()
+            .temperature_color(20.0, TemperatureUnit::Celsius)
+            .into();
+        assert_eq!(dashboard.color(20.0), Color::Rgb(r, g, b));
+
+        let theme = Theme {
+            temperature_scale: Some(ColorMap::Cividis),
+            ..Theme::dark()
+        };
+        let dashboard = dashboard.with_theme(theme);
+        let Rgb(r, g, b) = ColorMap::Cividis
+            .color(20.0, TemperatureUnit::Celsius)
+            .into();
+        assert_eq!(dashboard.color(20.0), Color::Rgb(r, g, b));
+    }
+}
@@ -0,0 +1,366 @@
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use egui::ecolor::Hsva;
+use egui::emath::TSTransform;
+use egui::epaint::ClippedShape;
+use egui::{pos2, vec2, Color32, Pos2, Sense, Shape, Stroke, Ui, Vec2};
+use lib_weather::WeatherData;
+use tracing::error;
+
+use crate::export::{self, ExportFormat};
+
+// flow lines drawn under a full cloud cover
+const MAX_LINES: f32 = 900.0;
+// points along each flow line, and the distance between them
+const LINE_STEPS: usize = 60;
+const STEP: f32 = 0.004;
+// most precipitation particles, reached around heavy rain
+const MAX_PARTICLES: f64 = 600.0;
+
+/// The conditions a piece is generated from.
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Params {
+    /// °F
+    temperature: f64,
+    /// mph
+    wind_speed: f64,
+    wind_gust: f64,
+    /// degrees the wind blows from
+    wind_bearing: f64,
+    /// 0 to 1
+    cloud_cover: f64,
+    /// inches per hour
+    precip_intensity: f64,
+    precip_probability: f64,
+    snow: bool,
+    /// 0 new moon, 0.5 full moon
+    moon_phase: f64,
+}
+
+impl Params {
+    fn from_data(data: &dyn WeatherData) -> Option<Self> {
+        let current = data.current()?;
+        let moon_phase = data
+            .daily()
+            .and_then(|days| days.first())
+            .and_then(|day| day.moon_phase)
+            .unwrap_or_else(|| lib_astro::moon_phase(current.time));
+        let wind_speed = current.wind_speed.unwrap_or_default();
+
+        Some(Self {
+            temperature: current.temperature.unwrap_or(60.0),
+            wind_speed,
+            wind_gust: current.wind_gust.unwrap_or(wind_speed),
+            wind_bearing: current.wind_bearing.unwrap_or_default(),
+            cloud_cover: current.cloud_cover.unwrap_or_default().clamp(0.0, 1.0),
+            precip_intensity: current.precip_intensity.unwrap_or_default(),
+            precip_probability: current.precip_probability.unwrap_or_default(),
+            snow: current.precip_type.as_deref() == Some("snow"),
+            moon_phase,
+        })
+    }
+
+    /// FNV-1a hash of the conditions, so the same forecast always seeds the same piece.
+    fn seed(&self) -> u64 {
+        let values = [
+            self.temperature,
+            self.wind_speed,
+            self.wind_gust,
+            self.wind_bearing,
+            self.cloud_cover,
+            self.precip_intensity,
+            self.precip_probability,
+            f64::from(u8::from(self.snow)),
+            self.moon_phase,
+        ];
+        let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+        for byte in values.iter().flat_map(|v| v.to_bits().to_le_bytes()) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        hash
+    }
+}
+
+/// `SplitMix64`, small and stable across platforms and releases.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    #[allow(clippy::cast_precision_loss)]
+    fn unit(&mut self) -> f32 {
+        (self.next() >> 40) as f32 / (1_u64 << 24) as f32
+    }
+
+    fn range(&mut self, low: f32, high: f32) -> f32 {
+        low + (high - low) * self.unit()
+    }
+
+    fn point(&mut self) -> Pos2 {
+        pos2(self.unit(), self.unit())
+    }
+}
+
+/// A generated composition, laid out on a unit square.
+#[derive(PartialEq, Debug)]
+struct Piece {
+    seed: u64,
+    background: Color32,
+    shapes: Vec<Shape>,
+}
+
+impl Piece {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn generate(params: &Params) -> Self {
+        let seed = params.seed();
+        let mut rng = Rng(seed);
+
+        // temperature picks the hue, from blue when freezing to red when hot
+        let warmth = (params.temperature / 100.0).clamp(0.0, 1.0) as f32;
+        let hue = 0.62 * (1.0 - warmth);
+        let palette: Vec<Color32> = (0..4)
+            .map(|_| {
+                let h = (hue + rng.range(-0.06, 0.06)).rem_euclid(1.0);
+                Hsva::new(h, rng.range(0.45, 0.9), rng.range(0.7, 1.0), 1.0).into()
+            })
+            .collect();
+        // clouds grey out and lighten the sky
+        let cloud = params.cloud_cover as f32;
+        let background = Hsva::new(hue, 0.5 * (1.0 - 0.7 * cloud), 0.1 + 0.12 * cloud, 1.0).into();
+
+        let mut shapes = Vec::new();
+        let flow = FlowField::new(params, &mut rng);
+        let lines = (MAX_LINES * (0.15 + 0.85 * cloud)) as usize;
+        for i in 0..lines {
+            let mut p = rng.point();
+            let mut points = Vec::with_capacity(LINE_STEPS);
+            for _ in 0..LINE_STEPS {
+                points.push(p);
+                p += flow.direction(p) * STEP;
+                if !(0.0..=1.0).contains(&p.x) || !(0.0..=1.0).contains(&p.y) {
+                    break;
+                }
+            }
+            let color = palette[i % palette.len()].gamma_multiply(rng.range(0.25, 0.8));
+            shapes.push(Shape::line(
+                points,
+                Stroke::new(rng.range(0.001, 0.004), color),
+            ));
+        }
+
+        moon(&mut shapes, params.moon_phase, background, &mut rng);
+
+        // precipitation falls through it all, slanted by the wind
+        let particles = (params.precip_probability.clamp(0.0, 1.0)
+            * (params.precip_intensity * 2000.0).min(MAX_PARTICLES))
+            as usize;
+        let fall = (vec2(0.0, 1.0) + flow.wind * 0.5).normalized();
+        for _ in 0..particles {
+            let p = rng.point();
+            if params.snow {
+                let color = Color32::WHITE.gamma_multiply(rng.range(0.5, 0.9));
+                shapes.push(Shape::circle_filled(p, rng.range(0.002, 0.005), color));
+            } else {
+                let color = Color32::from_rgb(170, 200, 255).gamma_multiply(rng.range(0.4, 0.8));
+                shapes.push(Shape::line_segment(
+                    [p, p + fall * rng.range(0.01, 0.03)],
+                    Stroke::new(0.0015, color),
+                ));
+            }
+        }
+
+        Self {
+            seed,
+            background,
+            shapes,
+        }
+    }
+
+    /// The shapes scaled to a square with its top left corner at `origin`.
+    fn shapes_at(&self, origin: Pos2, side: f32) -> impl Iterator<Item = Shape> + '_ {
+        let transform = TSTransform::new(origin.to_vec2(), side);
+        self.shapes.iter().cloned().map(move |mut shape| {
+            shape.transform(transform);
+            shape
+        })
+    }
+}
+
+/// Directions the flow lines follow: downwind, bent by waves that grow with the wind.
+struct FlowField {
+    wind: Vec2,
+    turbulence: f32,
+    waves: [(f32, f32, f32, f32); 3],
+}
+
+impl FlowField {
+    #[allow(clippy::cast_possible_truncation)]
+    fn new(params: &Params, rng: &mut Rng) -> Self {
+        // the bearing is where the wind comes from, with north up the canvas
+        let downwind = (params.wind_bearing as f32 + 180.0).to_radians();
+        let strength = params.wind_gust.max(params.wind_speed) as f32;
+        Self {
+            wind: vec2(downwind.sin(), -downwind.cos()),
+            turbulence: (strength / 30.0).clamp(0.1, 2.0) * FRAC_PI_2,
+            waves: [(); 3].map(|()| {
+                (
+                    rng.range(2.0, 7.0),
+                    rng.range(2.0, 7.0),
+                    rng.range(0.0, TAU),
+                    rng.range(0.0, TAU),
+                )
+            }),
+        }
+    }
+
+    fn direction(&self, p: Pos2) -> Vec2 {
+        let bend: f32 = self
+            .waves
+            .iter()
+            .map(|(fx, fy, px, py)| (p.x * fx + px).sin() * (p.y * fy + py).cos())
+            .sum::<f32>()
+            / 3.0;
+        Vec2::angled(self.wind.angle() + self.turbulence * bend)
+    }
+}
+
+// The moon in its phase, with a faint halo, somewhere in the upper half.
+fn moon(shapes: &mut Vec<Shape>, phase: f64, background: Color32, rng: &mut Rng) {
+    #[allow(clippy::cast_possible_truncation)]
+    let lit = lib_astro::illumination(phase) as f32;
+    let center = pos2(rng.range(0.15, 0.85), rng.range(0.1, 0.45));
+    let radius = 0.06;
+    let light = Color32::from_rgb(250, 245, 225);
+
+    shapes.push(Shape::circle_stroke(
+        center,
+        radius * 1.6,
+        Stroke::new(0.004, light.gamma_multiply(0.15)),
+    ));
+    shapes.push(Shape::circle_filled(center, radius, light));
+    if lit < 0.98 {
+        // waxing moons are lit from the right, so the shadow sits left
+        let side = if phase < 0.5 { -1.0 } else { 1.0 };
+        shapes.push(Shape::circle_filled(
+            center + vec2(side * 2.0 * radius * lit, 0.0),
+            radius,
+            background,
+        ));
+    }
+}
+
+/// Abstract art generated from the current conditions.
+#[derive(Default)]
+pub struct ArtView {
+    piece: Option<Piece>,
+    export: Option<ExportFormat>,
+    screenshot: Option<export::Screenshot>,
+}
+
+impl ArtView {
+    pub fn update_data(&mut self, data: &dyn WeatherData) {
+        if let Some(params) = Params::from_data(data) {
+            self.piece = Some(Piece::generate(&params));
+        }
+    }
+
+    pub fn update(&mut self, ui: &mut Ui) {
+        if self.screenshot.as_ref().is_some_and(|s| s.poll(ui.ctx())) {
+            self.screenshot = None;
+        }
+
+        let Some(piece) = &self.piece else {
+            ui.label("Fetch the weather to generate a piece from it.");
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            let seed = format!("{:016x}", piece.seed);
+            ui.label("Seed:");
+            ui.monospace(&seed);
+            if ui
+                .small_button("📋")
+                .on_hover_text("Copy the seed")
+                .clicked()
+            {
+                ui.ctx().copy_text(seed);
+            }
+            ui.separator();
+            if let Some(format) = export::buttons(ui) {
+                self.export = Some(format);
+            }
+        });
+
+        let side = ui.available_size().min_elem().max(64.0);
+        let (rect, _) = ui.allocate_exact_size(Vec2::splat(side), Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, piece.background);
+        painter.extend(piece.shapes_at(rect.min, side));
+
+        let Some(format) = self.export.take() else {
+            return;
+        };
+        let file_name = export::file_name(&format!("art-{:016x}", piece.seed), format);
+        match format {
+            ExportFormat::Png => {
+                self.screenshot = Some(export::Screenshot::request(ui.ctx(), rect, file_name));
+            }
+            ExportFormat::Svg => {
+                let shapes: Vec<ClippedShape> = piece
+                    .shapes_at(rect.min, side)
+                    .map(|shape| ClippedShape {
+                        clip_rect: rect,
+                        shape,
+                    })
+                    .collect();
+                let svg = export::svg(&shapes, rect, piece.background);
+                if let Err(err) = export::save(&file_name, svg.as_bytes()) {
+                    error!("Failed to export {file_name}: {err}");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn params() -> Params {
+        Params {
+            temperature: 68.0,
+            wind_speed: 12.0,
+            wind_gust: 20.0,
+            wind_bearing: 270.0,
+            cloud_cover: 0.4,
+            precip_intensity: 0.05,
+            precip_probability: 0.8,
+            snow: false,
+            moon_phase: 0.3,
+        }
+    }
+
+    #[test]
+    fn same_conditions_same_piece() {
+        assert_eq!(Piece::generate(&params()), Piece::generate(&params()));
+    }
+
+    #[test]
+    fn conditions_change_the_piece() {
+        let piece = Piece::generate(&params());
+        let cloudier = Piece::generate(&Params {
+            cloud_cover: 0.9,
+            ..params()
+        });
+        assert_ne!(piece.seed, cloudier.seed);
+        assert!(cloudier.shapes.len() > piece.shapes.len());
+    }
+}
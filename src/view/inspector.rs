@@ -0,0 +1,208 @@
+use egui::{CollapsingHeader, Grid, Id, RichText, ScrollArea, Ui};
+use lib_weather::inspect::{self, Exchange};
+use serde_json::Value;
+
+/// Shows the last raw response from each provider request beside how it was
+/// parsed, to tell a field the provider omitted from one that failed to parse.
+#[derive(Default)]
+pub struct InspectorView {
+    // generation of `exchanges`, to refresh them only when new ones arrive
+    generation: Option<u64>,
+    exchanges: Vec<Exchange>,
+    // index into `exchanges`
+    selected: usize,
+}
+
+impl InspectorView {
+    pub fn update(&mut self, ui: &mut Ui) {
+        let generation = inspect::generation();
+        if self.generation != Some(generation) {
+            self.generation = Some(generation);
+            self.exchanges = inspect::exchanges();
+        }
+
+        if self.exchanges.is_empty() {
+            ui.label("No requests made yet.");
+            return;
+        }
+        ui.horizontal(|ui| {
+            for (i, exchange) in self.exchanges.iter().enumerate() {
+                ui.selectable_value(&mut self.selected, i, exchange.request.label());
+            }
+        });
+        ui.separator();
+
+        let Some(exchange) = self.exchanges.get(self.selected) else {
+            self.selected = 0;
+            return;
+        };
+        let raw: Option<Value> = serde_json::from_str(&exchange.body).ok();
+        ScrollArea::vertical().auto_shrink(false).show(ui, |ui| {
+            summary(ui, exchange);
+            if let Some(flags) = exchange.parsed.as_ref().and_then(|p| p.get("flags")) {
+                ui.add_space(8.0);
+                flags_ui(ui, flags);
+            }
+            ui.add_space(8.0);
+
+            ui.horizontal(|ui| {
+                ui.heading("Raw response");
+                if ui.button("Copy").clicked() {
+                    let text = raw
+                        .as_ref()
+                        .and_then(|raw| serde_json::to_string_pretty(raw).ok())
+                        .unwrap_or_else(|| exchange.body.clone());
+                    ui.ctx().copy_text(text);
+                }
+            });
+            match &raw {
+                Some(raw) => json_tree(ui, Id::new("inspector_raw"), "response", raw, None),
+                None => {
+                    ui.monospace(&exchange.body);
+                }
+            }
+
+            if let Some(parsed) = &exchange.parsed {
+                ui.add_space(8.0);
+                ui.heading("Parsed");
+                ui.weak("Fields shown as missing were not in the response at all.");
+                let raw = Some(raw.as_ref().map_or(Raw::Missing, Raw::Present));
+                json_tree(ui, Id::new("inspector_parsed"), "response", parsed, raw);
+            }
+        });
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn summary(ui: &mut Ui, exchange: &Exchange) {
+    Grid::new("inspector_summary")
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("URL");
+            ui.monospace(&exchange.url);
+            ui.end_row();
+
+            ui.label("Status");
+            match exchange.status {
+                Some(status) if (200..300).contains(&status) => ui.label(status.to_string()),
+                Some(status) => ui.colored_label(ui.visuals().warn_fg_color, status.to_string()),
+                None => ui.colored_label(ui.visuals().error_fg_color, "no response"),
+            };
+            ui.end_row();
+
+            ui.label("Latency");
+            ui.label(format!("{:.0} ms", exchange.latency_ms));
+            ui.end_row();
+
+            ui.label("Size");
+            ui.label(format!("{:.1} KiB", exchange.body.len() as f64 / 1024.0));
+            ui.end_row();
+
+            ui.label("Received");
+            let received = chrono::DateTime::from_timestamp(exchange.received, 0)
+                .map(|t| t.with_timezone(&chrono::Local).format("%F %T").to_string());
+            ui.label(received.unwrap_or_default());
+            ui.end_row();
+
+            if let Some(error) = &exchange.error {
+                ui.label("Error");
+                ui.colored_label(ui.visuals().error_fg_color, error);
+                ui.end_row();
+            }
+        });
+}
+
+// Pirate Weather's metadata about where the forecast came from.
+fn flags_ui(ui: &mut Ui, flags: &Value) {
+    ui.heading("Flags");
+    Grid::new("inspector_flags")
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| {
+            for (label, key) in [
+                ("Sources", "sources"),
+                ("Version", "version"),
+                ("Nearest station", "nearest-station"),
+                ("Process time", "processTime"),
+                ("Units", "units"),
+            ] {
+                ui.label(label);
+                match flags.get(key) {
+                    Some(Value::Array(items)) => {
+                        ui.label(items.iter().map(leaf_text).collect::<Vec<_>>().join(", "))
+                    }
+                    Some(value) => ui.label(leaf_text(value)),
+                    None => ui.weak("missing"),
+                };
+                ui.end_row();
+            }
+        });
+}
+
+// Where a parsed value was in the raw response.
+#[derive(Clone, Copy)]
+enum Raw<'a> {
+    Present(&'a Value),
+    Missing,
+}
+
+impl<'a> Raw<'a> {
+    fn get(self, index: impl serde_json::value::Index) -> Raw<'a> {
+        match self {
+            Raw::Present(value) => value.get(index).map_or(Raw::Missing, Raw::Present),
+            Raw::Missing => Raw::Missing,
+        }
+    }
+}
+
+// `value` as a tree, collapsed below the top level. `raw` is the matching part
+// of the response when showing parsed values.
+fn json_tree(ui: &mut Ui, id: Id, key: &str, value: &Value, raw: Option<Raw<'_>>) {
+    let child_raw = |child: &str| raw.map(|raw| raw.get(child));
+    match value {
+        Value::Object(fields) => {
+            CollapsingHeader::new(RichText::new(format!("{key} {{{}}}", fields.len())).monospace())
+                .id_salt(id)
+                .default_open(key == "response")
+                .show(ui, |ui| {
+                    for (child, value) in fields {
+                        json_tree(ui, id.with(child), child, value, child_raw(child));
+                    }
+                });
+        }
+        Value::Array(items) => {
+            CollapsingHeader::new(RichText::new(format!("{key} [{}]", items.len())).monospace())
+                .id_salt(id)
+                .show(ui, |ui| {
+                    for (i, value) in items.iter().enumerate() {
+                        let raw = raw.map(|raw| raw.get(i));
+                        json_tree(ui, id.with(i), &i.to_string(), value, raw);
+                    }
+                });
+        }
+        leaf => {
+            ui.horizontal(|ui| {
+                ui.monospace(format!("{key}:"));
+                match (leaf, raw) {
+                    (Value::Null, Some(Raw::Missing)) => {
+                        ui.colored_label(ui.visuals().warn_fg_color, "missing");
+                    }
+                    (Value::Null, _) => {
+                        ui.weak("null");
+                    }
+                    _ => {
+                        ui.monospace(leaf_text(leaf));
+                    }
+                }
+            });
+        }
+    }
+}
+
+fn leaf_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
@@ -1,44 +1,508 @@
-use egui::Ui;
-use egui_extras::syntax_highlighting;
+use std::collections::VecDeque;
+
+use egui::text::{LayoutJob, TextFormat};
+use egui::{Color32, DragValue, FontId, Label, ScrollArea, TextEdit, Ui};
 use tokio::sync::mpsc::{error::TryRecvError, Receiver};
-use tracing::error;
+use tracing::level_filters::LevelFilter;
+use tracing::{error, Level};
+
+use crate::export;
+use crate::{dropped_logs, LogEvent, LogFilter};
+
+/// Logs kept by default before the oldest are dropped.
+pub const DEFAULT_LOG_LIMIT: usize = 10_000;
+
+const FONT_SIZE: f32 = 12.0;
+// levels in the order their toggles are shown
+const LEVELS: [Level; 5] = [
+    Level::ERROR,
+    Level::WARN,
+    Level::INFO,
+    Level::DEBUG,
+    Level::TRACE,
+];
+const INFO: Color32 = Color32::from_rgb(110, 190, 255);
+const DEBUG: Color32 = Color32::from_rgb(170, 140, 230);
+const TRACE: Color32 = Color32::GRAY;
+// levels each crate can be set to
+const LEVEL_FILTERS: [LevelFilter; 6] = [
+    LevelFilter::OFF,
+    LevelFilter::ERROR,
+    LevelFilter::WARN,
+    LevelFilter::INFO,
+    LevelFilter::DEBUG,
+    LevelFilter::TRACE,
+];
+// behind text matching the search
+const MATCH: Color32 = Color32::from_rgba_premultiplied(120, 100, 0, 160);
+
+/// Formats logs can be copied or saved in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum LogFormat {
+    Text,
+    JsonLines,
+}
+
+impl LogFormat {
+    fn label(self) -> &'static str {
+        match self {
+            LogFormat::Text => "Text",
+            LogFormat::JsonLines => "JSON lines",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            LogFormat::Text => "log",
+            LogFormat::JsonLines => "jsonl",
+        }
+    }
+
+    fn write<'a>(self, logs: impl Iterator<Item = &'a LogEvent>) -> String {
+        let mut out = String::new();
+        for log in logs {
+            out.push_str(&match self {
+                LogFormat::Text => log.to_text(),
+                LogFormat::JsonLines => log.to_json(),
+            });
+            out.push('\n');
+        }
+        out
+    }
+}
+
+// A log, with its message lowercased once for searching.
+struct Entry {
+    log: LogEvent,
+    lower: String,
+}
 
 pub struct LogsView {
-    rx: Receiver<String>,
-    logs: Vec<String>,
-    logs_display: String,
+    rx: Receiver<LogEvent>,
+    /// the newest `limit` logs, oldest first
+    logs: VecDeque<Entry>,
+    limit: usize,
+    /// logs dropped from the front to stay within `limit`
+    evicted: usize,
+    /// `dropped_logs()` when last cleared, so only later drops are counted
+    dropped_before: usize,
+    /// logs ever removed from the front, so the first kept is at this index
+    first: usize,
+    /// indices of the logs passing `shown_for`, kept up to date as logs come and go
+    shown: VecDeque<usize>,
+    /// levels and lowercased search `shown` was built for
+    shown_for: ([bool; 5], String),
+    /// shown levels, in the order of `LEVELS`
+    levels: [bool; 5],
+    search: String,
+    /// keep scrolled to the newest log
+    follow: bool,
+    /// what gets logged, when logging was set up with a filter
+    filter: Option<LogFilter>,
+    /// format logs are copied and saved in
+    format: LogFormat,
 }
 
 impl LogsView {
     #[must_use]
-    pub fn new(rx: Receiver<String>) -> Self {
+    pub fn new(rx: Receiver<LogEvent>) -> Self {
         Self {
             rx,
-            logs: Vec::new(),
-            logs_display: String::new(),
+            logs: VecDeque::new(),
+            limit: DEFAULT_LOG_LIMIT,
+            evicted: 0,
+            dropped_before: dropped_logs(),
+            first: 0,
+            shown: VecDeque::new(),
+            shown_for: ([true; 5], String::new()),
+            levels: [true; 5],
+            search: String::new(),
+            follow: true,
+            filter: None,
+            format: LogFormat::Text,
         }
     }
 
-    // check for new logs and if found, append them to stored logs
-    // the update loop runs with enough frequency and our expected
-    // throughput is low enough that checking once each call is more than enough.
+    /// Allow changing what gets logged from the view.
+    pub fn set_filter(&mut self, filter: LogFilter) {
+        self.filter = Some(filter);
+    }
+
+    #[must_use]
+    pub fn filter(&self) -> Option<&LogFilter> {
+        self.filter.as_ref()
+    }
+
+    pub fn filter_mut(&mut self) -> Option<&mut LogFilter> {
+        self.filter.as_mut()
+    }
+
+    /// Keep at most `limit` logs, dropping the oldest beyond it.
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit.max(1);
+        self.truncate();
+    }
+
+    // take every log waiting in the channel, so bursts don't back up and get
+    // dropped before the view sees them
     pub fn check_logs(&mut self) {
-        match self.rx.try_recv() {
-            Ok(log) => {
-                self.logs_display.push_str(&log);
-                self.logs.push(log);
+        loop {
+            match self.rx.try_recv() {
+                Ok(log) => self.push(log),
+                Err(TryRecvError::Empty) => break,
+                Err(e) => {
+                    error!("Logs sender disconnected: {e}");
+                    break;
+                }
             }
-            Err(TryRecvError::Empty) => {}
-            Err(e) => error!("Logs sender disconnected: {e}"),
         }
+        self.truncate();
+    }
+
+    fn push(&mut self, log: LogEvent) {
+        let entry = Entry {
+            lower: log.message.to_ascii_lowercase(),
+            log,
+        };
+        if self.is_shown(&entry) {
+            self.shown.push_back(self.first + self.logs.len());
+        }
+        self.logs.push_back(entry);
+    }
+
+    fn truncate(&mut self) {
+        let excess = self.logs.len().saturating_sub(self.limit);
+        self.logs.drain(..excess);
+        self.evicted += excess;
+        self.forget(excess);
+    }
+
+    // Move past `count` logs removed from the front.
+    fn forget(&mut self, count: usize) {
+        self.first += count;
+        while self.shown.front().is_some_and(|&i| i < self.first) {
+            self.shown.pop_front();
+        }
+    }
+
+    // Rebuild the shown logs only when the levels or search have changed.
+    fn refresh_shown(&mut self) {
+        let wanted = (self.levels, self.search.to_ascii_lowercase());
+        if wanted == self.shown_for {
+            return;
+        }
+        self.shown_for = wanted;
+        self.shown = (0..self.logs.len())
+            .filter(|&i| self.is_shown(&self.logs[i]))
+            .map(|i| self.first + i)
+            .collect();
     }
 
     pub fn update(&mut self, ui: &Ui) {
+        self.refresh_shown();
+        let shown = std::mem::take(&mut self.shown);
+
+        egui::TopBottomPanel::top("logs_toolbar").show(ui.ctx(), |ui| {
+            ui.horizontal(|ui| self.toolbar(ui, shown.len()));
+            ui.horizontal(|ui| self.actions(ui, &shown));
+        });
+
         egui::CentralPanel::default().show(ui.ctx(), |ui| {
-            // TODO improvements: highlighting on log syntax, colored differently for log levels
-            let language = "rs";
-            let theme = syntax_highlighting::CodeTheme::from_memory(ui.ctx(), ui.style());
-            syntax_highlighting::code_view_ui(ui, &theme, &self.logs_display, language);
+            let row_height = ui.fonts(|f| f.row_height(&FontId::monospace(FONT_SIZE)));
+            // only the visible rows are laid out, however many logs are kept
+            ScrollArea::both()
+                .auto_shrink(false)
+                .stick_to_bottom(self.follow)
+                .show_rows(ui, row_height, shown.len(), |ui, rows| {
+                    for &i in shown.range(rows) {
+                        let entry = &self.logs[i - self.first];
+                        ui.add(Label::new(log_line(ui, entry, &self.shown_for.1)).extend());
+                    }
+                });
         });
+
+        // put the shown logs back, unless Clear emptied the view meanwhile
+        if self.shown.is_empty() && !self.logs.is_empty() {
+            self.shown = shown;
+        }
+    }
+
+    fn toolbar(&mut self, ui: &mut Ui, shown: usize) {
+        for (level, shown) in LEVELS.iter().zip(&mut self.levels) {
+            let text = egui::RichText::new(level.as_str())
+                .monospace()
+                .color(level_color(ui, *level));
+            ui.toggle_value(shown, text);
+        }
+        ui.separator();
+
+        ui.add(
+            TextEdit::singleline(&mut self.search)
+                .hint_text("Search")
+                .desired_width(200.0),
+        );
+        if !self.search.is_empty() && ui.small_button("✖").clicked() {
+            self.search.clear();
+        }
+        ui.separator();
+
+        let label = if self.follow {
+            "⏸ Pause"
+        } else {
+            "▶ Follow"
+        };
+        if ui
+            .button(label)
+            .on_hover_text("Keep scrolled to the newest logs")
+            .clicked()
+        {
+            self.follow = !self.follow;
+        }
+
+        if let Some(filter) = &mut self.filter {
+            ui.separator();
+            ui.menu_button("Verbosity", |ui| verbosity_ui(ui, filter));
+        }
+
+        ui.separator();
+        let mut limit = self.limit;
+        ui.label("Keep");
+        if ui
+            .add(DragValue::new(&mut limit).range(100..=1_000_000).speed(100))
+            .on_hover_text("Older logs are dropped beyond this many")
+            .changed()
+        {
+            self.set_limit(limit);
+        }
+
+        ui.label(format!("{shown} of {} logs", self.logs.len()));
+        let dropped = self.dropped();
+        if dropped > 0 {
+            ui.colored_label(ui.visuals().warn_fg_color, format!("{dropped} dropped"))
+                .on_hover_text("Dropped as older than the limit, or arriving faster than shown");
+        }
+    }
+
+    // Copy, save or clear the logs. Copies and saves only take the logs shown.
+    fn actions(&mut self, ui: &mut Ui, shown: &VecDeque<usize>) {
+        for format in [LogFormat::Text, LogFormat::JsonLines] {
+            ui.selectable_value(&mut self.format, format, format.label());
+        }
+        let hover = if shown.len() < self.logs.len() {
+            "Only the logs matching the filter"
+        } else {
+            "Every log kept"
+        };
+        let text = || {
+            self.format
+                .write(shown.iter().map(|&i| &self.logs[i - self.first].log))
+        };
+
+        if ui.button("Copy all").on_hover_text(hover).clicked() {
+            ui.ctx().copy_text(text());
+        }
+        if ui.button("Save to file").on_hover_text(hover).clicked() {
+            let file_name = format!(
+                "horizon-logs-{}.{}",
+                chrono::Local::now().format("%Y%m%d-%H%M"),
+                self.format.extension()
+            );
+            if let Err(err) = export::save(&file_name, text().as_bytes()) {
+                error!("Failed to save logs: {err}");
+            }
+        }
+        if ui.button("Clear").clicked() {
+            self.clear();
+        }
+    }
+
+    fn clear(&mut self) {
+        let count = self.logs.len();
+        self.logs.clear();
+        self.forget(count);
+        self.evicted = 0;
+        self.dropped_before = dropped_logs();
+    }
+
+    // Logs dropped since the view was last cleared, whether evicted here or
+    // lost before reaching the view.
+    fn dropped(&self) -> usize {
+        self.evicted + dropped_logs().saturating_sub(self.dropped_before)
+    }
+
+    // Whether `entry` passes the levels and search `shown` is built for.
+    fn is_shown(&self, entry: &Entry) -> bool {
+        let (levels, search) = &self.shown_for;
+        let level_shown = LEVELS
+            .iter()
+            .position(|level| *level == entry.log.level)
+            .is_some_and(|i| levels[i]);
+        level_shown && (search.is_empty() || entry.lower.contains(search.as_str()))
+    }
+}
+
+// A level picker for each crate.
+fn verbosity_ui(ui: &mut Ui, filter: &mut LogFilter) {
+    if let Some(env) = filter.from_env() {
+        ui.label(format!("Using RUST_LOG={env} until a level is changed."));
+    }
+    let mut changed = None;
+    egui::Grid::new("verbosity_grid")
+        .num_columns(2)
+        .show(ui, |ui| {
+            for (target, level) in filter.levels() {
+                ui.label(target);
+                egui::ComboBox::from_id_salt(target)
+                    .selected_text(level.to_string())
+                    .show_ui(ui, |ui| {
+                        for option in LEVEL_FILTERS {
+                            if ui
+                                .selectable_label(level == option, option.to_string())
+                                .clicked()
+                            {
+                                changed = Some((target, option));
+                            }
+                        }
+                    });
+                ui.end_row();
+            }
+        });
+    if let Some((target, level)) = changed {
+        filter.set_level(target, level);
+    }
+}
+
+// A log laid out as time, level, target and message, with `search` highlighted
+// in the message.
+fn log_line(ui: &Ui, entry: &Entry, search: &str) -> LayoutJob {
+    let log = &entry.log;
+    let font = FontId::monospace(FONT_SIZE);
+    let weak = TextFormat::simple(font.clone(), ui.visuals().weak_text_color());
+    let text = TextFormat::simple(font.clone(), ui.visuals().text_color());
+
+    let mut job = LayoutJob::default();
+    job.append(
+        &log.time.format("%H:%M:%S%.3f ").to_string(),
+        0.0,
+        weak.clone(),
+    );
+    job.append(
+        &format!("{:<5} ", log.level.as_str()),
+        0.0,
+        TextFormat::simple(font, level_color(ui, log.level)),
+    );
+    job.append(&format!("{}: ", log.target), 0.0, weak);
+
+    // ASCII lowercasing keeps byte offsets the same as in the message
+    let mut start = 0;
+    if !search.is_empty() {
+        for (at, found) in entry.lower.match_indices(search) {
+            job.append(&log.message[start..at], 0.0, text.clone());
+            job.append(
+                &log.message[at..at + found.len()],
+                0.0,
+                TextFormat {
+                    background: MATCH,
+                    ..text.clone()
+                },
+            );
+            start = at + found.len();
+        }
+    }
+    job.append(&log.message[start..], 0.0, text);
+    job
+}
+
+fn level_color(ui: &Ui, level: Level) -> Color32 {
+    match level {
+        Level::ERROR => ui.visuals().error_fg_color,
+        Level::WARN => ui.visuals().warn_fg_color,
+        Level::INFO => INFO,
+        Level::DEBUG => DEBUG,
+        _ => TRACE,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    fn event(message: &str) -> LogEvent {
+        LogEvent {
+            time: chrono::Local::now(),
+            level: Level::INFO,
+            target: "horizon".into(),
+            message: message.into(),
+        }
+    }
+
+    #[test]
+    fn keeps_the_newest_logs() {
+        let (tx, rx) = mpsc::channel(10);
+        let mut view = LogsView::new(rx);
+        view.set_limit(3);
+        for i in 0..5 {
+            tx.try_send(event(&i.to_string())).unwrap();
+        }
+
+        view.check_logs();
+        let kept: Vec<_> = view
+            .logs
+            .iter()
+            .map(|entry| entry.log.message.as_str())
+            .collect();
+        assert_eq!(kept, ["2", "3", "4"]);
+        assert_eq!(view.evicted, 2);
+        assert_eq!(view.shown, [2, 3, 4]);
+
+        view.clear();
+        assert_eq!(view.dropped(), 0);
+    }
+
+    #[test]
+    fn keeps_the_shown_logs_up_to_date() {
+        let (tx, rx) = mpsc::channel(10);
+        let mut view = LogsView::new(rx);
+        view.set_limit(4);
+        for message in ["Fetched", "failed", "FAILED again", "ok"] {
+            tx.try_send(event(message)).unwrap();
+        }
+        view.check_logs();
+
+        view.search = "Fail".into();
+        view.refresh_shown();
+        assert_eq!(view.shown, [1, 2]);
+
+        // new logs are matched as they arrive, and evicted ones let go
+        tx.try_send(event("fail three")).unwrap();
+        tx.try_send(event("fine")).unwrap();
+        view.check_logs();
+        assert_eq!(view.shown, [2, 4]);
+
+        view.levels[2] = false;
+        view.refresh_shown();
+        assert!(view.shown.is_empty());
+
+        view.levels[2] = true;
+        view.refresh_shown();
+        view.clear();
+        tx.try_send(event("failing later")).unwrap();
+        view.check_logs();
+        assert_eq!(view.shown, [6]);
+        assert_eq!(view.logs[6 - view.first].log.message, "failing later");
+    }
+
+    #[test]
+    fn formats_a_line_per_log() {
+        let logs = [event("one"), event("two")];
+        let text = LogFormat::Text.write(logs.iter());
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.ends_with("INFO  horizon: two\n"));
+
+        let jsonl = LogFormat::JsonLines.write(logs.iter());
+        assert!(jsonl
+            .lines()
+            .all(|line| serde_json::from_str::<serde_json::Value>(line).is_ok()));
     }
 }
@@ -1,5 +1,13 @@
+pub mod art;
+pub mod inspector;
 pub mod logs;
+pub mod playback;
+pub mod theme;
 pub mod weather;
 
+pub use art::ArtView;
+pub use inspector::InspectorView;
 pub use logs::LogsView;
+pub use playback::Playback;
+pub use theme::ThemeEditor;
 pub use weather::WeatherView;
@@ -0,0 +1,303 @@
+use chrono::{DateTime, Days, NaiveDate};
+use chrono_tz::Tz;
+use egui::{DragValue, Slider, Ui};
+use egui_extras::DatePickerButton;
+use tracing::warn;
+
+// most days that can be loaded at once, to keep the number of requests down
+const MAX_DAYS: u32 = 7;
+
+#[derive(PartialEq)]
+enum PlaybackState {
+    Idle,
+    Loading,
+    Paused,
+    Playing,
+}
+
+/// Replays past days of weather through the widgets, an hour at a time.
+pub struct Playback {
+    date: NaiveDate,
+    days: u32,
+    /// forecast hours played per second
+    speed: f32,
+    state: PlaybackState,
+    /// hours in each loaded day
+    hours: Vec<Vec<i64>>,
+    timezone: String,
+    /// index into all the loaded hours, fractional while playing
+    position: f32,
+    /// (day, hour) last handed to the widgets
+    shown: Option<(usize, usize)>,
+    requested: Option<Vec<NaiveDate>>,
+    stopped: bool,
+}
+
+impl Default for Playback {
+    fn default() -> Self {
+        let yesterday = chrono::Local::now()
+            .date_naive()
+            .checked_sub_days(Days::new(1))
+            .unwrap_or_default();
+        Self {
+            date: yesterday,
+            days: 1,
+            speed: 4.0,
+            state: PlaybackState::Idle,
+            hours: Vec::new(),
+            timezone: String::new(),
+            position: 0.0,
+            shown: None,
+            requested: None,
+            stopped: false,
+        }
+    }
+}
+
+impl Playback {
+    /// Whether past weather is being shown instead of the live forecast.
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        matches!(self.state, PlaybackState::Paused | PlaybackState::Playing)
+    }
+
+    #[must_use]
+    pub fn is_playing(&self) -> bool {
+        self.state == PlaybackState::Playing
+    }
+
+    /// Days the user asked to load since the last call.
+    pub fn take_request(&mut self) -> Option<Vec<NaiveDate>> {
+        self.requested.take()
+    }
+
+    /// Whether the user left playback since the last call.
+    pub fn take_stopped(&mut self) -> bool {
+        std::mem::take(&mut self.stopped)
+    }
+
+    /// The requested days arrived, with the hours in each.
+    pub fn loaded(&mut self, hours: Vec<Vec<i64>>, timezone: &str) {
+        self.hours = hours;
+        timezone.clone_into(&mut self.timezone);
+        self.position = 0.0;
+        self.shown = None;
+        self.state = if self.total_hours() == 0 {
+            PlaybackState::Idle
+        } else {
+            PlaybackState::Paused
+        };
+    }
+
+    /// Keep the requested days before `today`, which have history to load,
+    /// giving up on the request when there are none.
+    pub fn past_days(&mut self, dates: Vec<NaiveDate>, today: NaiveDate) -> Option<Vec<NaiveDate>> {
+        let requested = dates.len();
+        let past: Vec<_> = dates.into_iter().filter(|date| *date < today).collect();
+        if past.is_empty() {
+            warn!("No weather history yet for {today} or later");
+            self.failed();
+            return None;
+        }
+        if past.len() < requested {
+            warn!(
+                "Loading {} of {requested} days, as history ends before {today}",
+                past.len()
+            );
+        }
+        Some(past)
+    }
+
+    pub fn failed(&mut self) {
+        self.state = PlaybackState::Idle;
+    }
+
+    /// Advance by `dt` seconds, returning the day and hour to show if it changed.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    pub fn tick(&mut self, dt: f32) -> Option<(usize, i64)> {
+        if !self.is_active() {
+            return None;
+        }
+
+        let last = self.total_hours().saturating_sub(1) as f32;
+        if self.is_playing() {
+            self.position += dt * self.speed;
+            if self.position >= last {
+                self.position = last;
+                self.state = PlaybackState::Paused;
+            }
+        }
+
+        let (day, hour) = self.locate(self.position as usize)?;
+        if self.shown == Some((day, hour)) {
+            return None;
+        }
+        self.shown = Some((day, hour));
+        Some((day, self.hours[day][hour]))
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    pub fn ui(&mut self, ui: &mut Ui) {
+        egui::Grid::new("playback_grid")
+            .num_columns(2)
+            .spacing([40.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("From: ");
+                ui.add(DatePickerButton::new(&mut self.date).id_salt("playback_date"));
+                ui.end_row();
+
+                ui.label("Days: ");
+                ui.add(DragValue::new(&mut self.days).range(1..=MAX_DAYS));
+                ui.end_row();
+
+                let loading = self.state == PlaybackState::Loading;
+                if ui
+                    .add_enabled(!loading, egui::Button::new("Load"))
+                    .clicked()
+                {
+                    self.requested = Some(
+                        (0..self.days)
+                            .filter_map(|i| self.date.checked_add_days(Days::new(i.into())))
+                            .collect(),
+                    );
+                    self.state = PlaybackState::Loading;
+                }
+                if loading {
+                    ui.spinner();
+                } else {
+                    ui.label("");
+                }
+                ui.end_row();
+            });
+
+        if !self.is_active() {
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            let label = if self.is_playing() { "⏸" } else { "▶" };
+            if ui.button(label).clicked() {
+                self.state = if self.is_playing() {
+                    PlaybackState::Paused
+                } else {
+                    // start over once the end is reached
+                    if self.position as usize + 1 >= self.total_hours() {
+                        self.position = 0.0;
+                    }
+                    PlaybackState::Playing
+                };
+            }
+            if ui.button("⏹").on_hover_text("Back to live data").clicked() {
+                self.state = PlaybackState::Idle;
+                self.hours.clear();
+                self.shown = None;
+                self.stopped = true;
+            }
+        });
+
+        ui.add(
+            Slider::new(&mut self.speed, 1.0..=24.0)
+                .text("hours/s")
+                .logarithmic(true),
+        );
+
+        let last = self.total_hours().saturating_sub(1);
+        let mut index = self.position as usize;
+        let tz: Tz = self.timezone.parse().unwrap_or(chrono_tz::UTC);
+        let time = |i: usize| {
+            self.locate(i)
+                .map(|(day, hour)| self.hours[day][hour])
+                .and_then(|t| DateTime::from_timestamp(t, 0))
+                .map_or_else(
+                    || "--".into(),
+                    |dt| dt.with_timezone(&tz).format("%a %d %b %H:%M").to_string(),
+                )
+        };
+        let label = time(index);
+        if ui
+            .add(Slider::new(&mut index, 0..=last).show_value(false))
+            .changed()
+        {
+            self.position = index as f32;
+        }
+        ui.label(label);
+    }
+
+    fn total_hours(&self) -> usize {
+        self.hours.iter().map(Vec::len).sum()
+    }
+
+    // day and hour within it of the hour at `index` across all loaded days
+    fn locate(&self, mut index: usize) -> Option<(usize, usize)> {
+        for (day, hours) in self.hours.iter().enumerate() {
+            if index < hours.len() {
+                return Some((day, index));
+            }
+            index -= hours.len();
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn loaded(days: i64) -> Playback {
+        let mut playback = Playback::default();
+        let hours = (0..days)
+            .map(|d| (0..24).map(|h| (d * 24 + h) * 3600).collect())
+            .collect();
+        playback.loaded(hours, "UTC");
+        playback
+    }
+
+    #[test]
+    fn plays_across_days() {
+        let mut playback = loaded(2);
+        assert_eq!(playback.tick(0.0), Some((0, 0)));
+        // nothing new to show until the next hour
+        assert_eq!(playback.tick(0.0), None);
+
+        playback.state = PlaybackState::Playing;
+        playback.speed = 10.0;
+        assert_eq!(playback.tick(2.5), Some((1, 25 * 3600)));
+    }
+
+    #[test]
+    fn pauses_at_the_end() {
+        let mut playback = loaded(1);
+        playback.state = PlaybackState::Playing;
+        playback.speed = 24.0;
+        assert_eq!(playback.tick(10.0), Some((0, 23 * 3600)));
+        assert!(!playback.is_playing());
+        assert!(playback.is_active());
+    }
+
+    #[test]
+    fn loads_only_past_days() {
+        let mut playback = Playback::default();
+        let today = NaiveDate::from_ymd_opt(2025, 10, 19).unwrap();
+        let days = |from: u32, count: u32| {
+            (0..count)
+                .map(|i| NaiveDate::from_ymd_opt(2025, 10, from + i).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(playback.past_days(days(16, 3), today), Some(days(16, 3)));
+        assert_eq!(playback.past_days(days(17, 5), today), Some(days(17, 2)));
+
+        playback.state = PlaybackState::Loading;
+        assert_eq!(playback.past_days(days(19, 2), today), None);
+        assert!(playback.state == PlaybackState::Idle);
+    }
+}
@@ -0,0 +1,243 @@
+use egui::{Color32, Context, Grid, Rect, Response, Sense, Slider, TextEdit, Ui, Vec2, Window};
+use tracing::error;
+
+use crate::colormap::{ColorMap, TemperatureUnit};
+use crate::export;
+use crate::theme::Theme;
+
+/// Window for editing, sharing and importing the app's theme.
+pub struct ThemeEditor {
+    pub theme: Theme,
+    pub open: bool,
+    // JSON or TOML pasted in to import
+    import: String,
+    import_error: Option<String>,
+    // theme changed since it was last applied
+    dirty: bool,
+}
+
+impl ThemeEditor {
+    #[must_use]
+    pub fn new(theme: Theme) -> Self {
+        Self {
+            theme,
+            open: false,
+            import: String::new(),
+            import_error: None,
+            dirty: true,
+        }
+    }
+
+    /// Apply the theme to `ctx` if it changed since the last call.
+    pub fn apply(&mut self, ctx: &Context) {
+        if std::mem::take(&mut self.dirty) {
+            self.theme.apply(ctx);
+        }
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        let mut open = self.open;
+        Window::new("theme editor")
+            .open(&mut open)
+            .default_width(320.0)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| self.ui(ui));
+            });
+        self.open = open;
+    }
+
+    fn ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Start from:");
+            for preset in Theme::presets() {
+                if ui.button(&preset.name).clicked() {
+                    self.set_theme(preset);
+                }
+            }
+        });
+        ui.separator();
+
+        let theme = &mut self.theme;
+        let mut changed = false;
+        Grid::new("theme_grid")
+            .num_columns(2)
+            .spacing([40.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Name: ");
+                changed |= ui.text_edit_singleline(&mut theme.name).changed();
+                ui.end_row();
+
+                ui.label("Dark mode: ");
+                changed |= ui.checkbox(&mut theme.dark_mode, "").changed();
+                ui.end_row();
+
+                ui.label("Text size: ");
+                changed |= ui
+                    .add(Slider::new(&mut theme.text_size, 8.0..=24.0))
+                    .changed();
+                ui.end_row();
+
+                ui.label("Monospace text: ");
+                changed |= ui.checkbox(&mut theme.monospace, "").changed();
+                ui.end_row();
+
+                changed |= color_row(ui, "Accent: ", &mut theme.accent);
+                changed |= color_row(ui, "Status: ", &mut theme.status_ok);
+            });
+
+        egui::CollapsingHeader::new("Temperature")
+            .id_salt("theme_temperature")
+            .show(ui, |ui| {
+                let scale_label =
+                    |scale: Option<ColorMap>| scale.map_or("Theme steps", ColorMap::label);
+                egui::ComboBox::from_label("Scale")
+                    .selected_text(scale_label(theme.temperature_scale))
+                    .show_ui(ui, |ui| {
+                        for scale in std::iter::once(None).chain(ColorMap::ALL.map(Some)) {
+                            changed |= ui
+                                .selectable_value(
+                                    &mut theme.temperature_scale,
+                                    scale,
+                                    scale_label(scale),
+                                )
+                                .changed();
+                        }
+                    });
+
+                if theme.temperature_scale.is_none() {
+                    Grid::new("theme_temperature_grid")
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            let labels = [
+                                "Below 32°F: ",
+                                "32–50°F: ",
+                                "50–65°F: ",
+                                "65–75°F: ",
+                                "75–85°F: ",
+                                "85–95°F: ",
+                                "Above 95°F: ",
+                            ];
+                            for (label, color) in labels.iter().zip(&mut theme.temperature_steps) {
+                                changed |= color_row(ui, label, color);
+                            }
+                        });
+                }
+                ui.label("Preview, from -20°F to 120°F:");
+                temperature_preview(ui, theme);
+            });
+
+        egui::CollapsingHeader::new("Sky")
+            .id_salt("theme_sky")
+            .show(ui, |ui| {
+                let sky = &mut theme.sky;
+                Grid::new("theme_sky_grid").num_columns(2).show(ui, |ui| {
+                    for (label, color) in [
+                        ("Night: ", &mut sky.night),
+                        ("Astronomical twilight: ", &mut sky.astronomical_twilight),
+                        ("Nautical twilight: ", &mut sky.nautical_twilight),
+                        ("Civil twilight: ", &mut sky.civil_twilight),
+                        ("Day: ", &mut sky.day),
+                        ("Twilight shading: ", &mut sky.twilight_shade),
+                        ("Horizon: ", &mut sky.horizon),
+                        ("Labels: ", &mut sky.labels),
+                        ("Sun by day: ", &mut sky.sun),
+                        ("Sun in twilight: ", &mut sky.sun_twilight),
+                        ("Sun in deep twilight: ", &mut sky.sun_dark_twilight),
+                        ("Sun at night: ", &mut sky.sun_night),
+                    ] {
+                        changed |= color_row(ui, label, color);
+                    }
+                });
+            });
+        self.dirty |= changed;
+
+        ui.separator();
+        self.share_ui(ui);
+    }
+
+    fn share_ui(&mut self, ui: &mut Ui) {
+        ui.heading("Share");
+        ui.horizontal(|ui| {
+            if ui.button("Save as JSON").clicked() {
+                self.save(self.theme.to_json(), "json");
+            }
+            if ui.button("Save as TOML").clicked() {
+                self.save(self.theme.to_toml(), "toml");
+            }
+            if ui.button("Copy JSON").clicked() {
+                match self.theme.to_json() {
+                    Ok(json) => ui.ctx().copy_text(json),
+                    Err(err) => error!("Failed to copy theme: {err}"),
+                }
+            }
+        });
+
+        ui.label("Paste a theme as JSON or TOML to import it:");
+        ui.add(
+            TextEdit::multiline(&mut self.import)
+                .code_editor()
+                .desired_rows(4)
+                .desired_width(f32::INFINITY),
+        );
+        if ui.button("Import").clicked() {
+            match Theme::parse(&self.import) {
+                Ok(theme) => {
+                    self.set_theme(theme);
+                    self.import.clear();
+                    self.import_error = None;
+                }
+                Err(err) => self.import_error = Some(err.to_string()),
+            }
+        }
+        if let Some(err) = &self.import_error {
+            ui.colored_label(ui.visuals().error_fg_color, err);
+        }
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.dirty = true;
+    }
+
+    fn save(&self, text: anyhow::Result<String>, extension: &str) {
+        let name = self.theme.name.to_lowercase();
+        let file_name = format!(
+            "horizon-theme-{}.{extension}",
+            name.replace(|c: char| !c.is_alphanumeric(), "-")
+        );
+        if let Err(err) = text.and_then(|text| export::save(&file_name, text.as_bytes())) {
+            error!("Failed to save theme: {err}");
+        }
+    }
+}
+
+fn color_row(ui: &mut Ui, label: &str, color: &mut Color32) -> bool {
+    ui.label(label);
+    let response: Response = ui.color_edit_button_srgba(color);
+    ui.end_row();
+    response.changed()
+}
+
+// The temperature scale from -20°F to 120°F.
+#[allow(clippy::cast_precision_loss)]
+fn temperature_preview(ui: &mut Ui, theme: &Theme) {
+    let width = ui.available_width().min(300.0);
+    let (rect, _) = ui.allocate_exact_size(Vec2::new(width, 16.0), Sense::hover());
+    let painter = ui.painter_at(rect);
+    let columns = 70;
+    let column_width = rect.width() / columns as f32;
+    for i in 0..columns {
+        let temp = -20.0 + 140.0 * f64::from(i) / f64::from(columns);
+        let x = rect.left() + column_width * i as f32;
+        let column = Rect::from_min_size(
+            egui::pos2(x, rect.top()),
+            Vec2::new(column_width + 0.5, rect.height()),
+        );
+        painter.rect_filled(
+            column,
+            0.0,
+            theme.temperature_color(temp, TemperatureUnit::Fahrenheit),
+        );
+    }
+}
@@ -1,13 +1,15 @@
 use std::collections::BTreeSet;
 
 use anyhow::Result;
-use egui::{Color32, Id, IntoAtoms, Label, Layout, Modal, ScrollArea, Sense, TextEdit, Ui, Vec2};
+use egui::{Id, IntoAtoms, Label, Layout, Modal, ScrollArea, Sense, TextEdit, Ui, Vec2};
 use tokio::{
     runtime::Handle,
     sync::watch::{self, Receiver, Sender},
 };
 use tracing::{error, info};
 
+use super::Playback;
+use crate::theme::Theme;
 use crate::{FetchState, Widgets, A51_LAT, A51_LON};
 use lib_geolocate::{get_geo_location, GeoResponse};
 
@@ -23,6 +25,8 @@ pub struct WeatherView {
     rt: Option<Handle>,
     /// UI widgets
     pub widgets: Widgets,
+    /// replay of past weather
+    pub playback: Playback,
     open_widgets: BTreeSet<String>,
     /// location latitude
     pub latitude_str: String,
@@ -43,6 +47,7 @@ impl WeatherView {
         Self {
             rt,
             widgets: Widgets::new(),
+            playback: Playback::default(),
             open_widgets: BTreeSet::new(),
             latitude_str: String::from(A51_LAT),
             longitude_str: String::from(A51_LON),
@@ -76,9 +81,18 @@ impl WeatherView {
             .show(ui.ctx(), |ui| {
                 self.update_location(ui, fetch_state);
 
+                egui::CollapsingHeader::new("Playback")
+                    .id_salt("playback_header")
+                    .show(ui, |ui| self.playback.ui(ui));
+                ui.separator();
+
                 self.update_widget_toggle_pane(ui);
             });
 
+        egui::TopBottomPanel::bottom("timeline_panel").show(ui.ctx(), |ui| {
+            self.widgets.timeline(ui, self.tooltips_enabled);
+        });
+
         egui::CentralPanel::default().show(ui.ctx(), |ui| {
             // widget display area
             self.widgets.windows(ui.ctx(), &mut self.open_widgets);
@@ -142,7 +156,7 @@ impl WeatherView {
                     },
                     AsyncState::Succeeded => {
                         let (rect, _response) = ui.allocate_at_least(size, Sense::hover());
-                        ui.painter().circle_filled(rect.center(), r, Color32::LIGHT_GREEN);
+                        ui.painter().circle_filled(rect.center(), r, Theme::current(ui.ctx()).status_ok);
                     },
                     AsyncState::Failed => {
                         let (rect, _response) = ui.allocate_at_least(size, Sense::hover());
@@ -166,7 +180,7 @@ impl WeatherView {
                     ui.spinner();
                 } else {
                     let (rect, _response) = ui.allocate_at_least(size, Sense::hover());
-                    ui.painter().circle_filled(rect.center(), r, Color32::LIGHT_GREEN);
+                    ui.painter().circle_filled(rect.center(), r, Theme::current(ui.ctx()).status_ok);
                 }
 
                 ui.end_row();
@@ -244,6 +258,7 @@ impl WeatherView {
         if organize.clicked() {
             ui.ctx().memory_mut(egui::Memory::reset_areas);
         }
+        self.widgets.export_dashboard(ui);
         ui.toggle_value(
             &mut self.tooltips_enabled,
             ("Tooltips enabled").into_atoms(),
@@ -0,0 +1,249 @@
+use std::f32::consts::PI;
+
+use chrono::DateTime;
+use chrono_tz::Tz;
+use egui::{Align2, Color32, Context, FontId, Grid, Pos2, Stroke, Ui, Window};
+use egui_plot::{Corner, Legend, Line, Plot, PlotPoints};
+use lib_weather::{
+    AirQualityBlock, AirQualityData, AirQualityPoint, AqiScale, Pollutant, WeatherData,
+};
+
+use super::{in_day, time_cursor, DaySelection, View, Widget};
+
+#[derive(Default)]
+pub struct AirQualityWidget {
+    scale: AqiScale,
+    current: Option<AirQualityPoint>,
+    hourly: Option<AirQualityBlock>,
+    timezone: String,
+    selected: DaySelection,
+    forecast_time: Option<i64>,
+}
+
+impl Widget for AirQualityWidget {
+    fn name(&self) -> &'static str {
+        "air quality"
+    }
+
+    fn hover_text(&self, open: bool) -> String {
+        format!(
+            "Click to {} air quality data.",
+            if open { "hide" } else { "display" }
+        )
+    }
+
+    fn show(&mut self, ctx: &Context, open: &mut bool) {
+        Window::new(self.name())
+            .open(open)
+            .default_size(egui::vec2(512.0, 512.0))
+            .vscroll(true)
+            .show(ctx, |ui| self.ui(ui));
+    }
+
+    fn update_data(&mut self, _data: &dyn WeatherData) {}
+
+    fn update_air_quality(&mut self, data: &dyn AirQualityData) {
+        self.current = data.current().cloned();
+        self.hourly = data.hourly().cloned();
+        data.time().0.clone_into(&mut self.timezone);
+    }
+
+    fn select_day(&mut self, day: DaySelection) {
+        self.selected = day;
+    }
+
+    fn select_time(&mut self, time: Option<i64>) {
+        self.forecast_time = time;
+    }
+}
+
+impl View for AirQualityWidget {
+    fn ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.scale, AqiScale::UsEpa, AqiScale::UsEpa.name());
+            ui.radio_value(
+                &mut self.scale,
+                AqiScale::European,
+                AqiScale::European.name(),
+            );
+        });
+
+        // the forecast hour when one is picked on the timeline
+        let shown = self
+            .forecast_time
+            .and_then(|t| self.hourly.iter().flatten().find(|p| p.time == t))
+            .or(self.current.as_ref());
+        let Some(current) = shown else {
+            ui.label("No air quality data available.");
+            return;
+        };
+
+        self.draw_gauge(ui, current);
+        ui.separator();
+        self.pollutant_grid(ui, current);
+
+        if let Some(hourly) = &self.hourly {
+            ui.separator();
+            self.hourly_chart(ui, hourly);
+        }
+    }
+}
+
+impl AirQualityWidget {
+    #[allow(clippy::cast_possible_truncation)]
+    fn draw_gauge(&self, ui: &mut Ui, current: &AirQualityPoint) {
+        let desired_size = egui::vec2(ui.available_width(), 140.0);
+        let (response, painter) = ui.allocate_painter(desired_size, egui::Sense::hover());
+
+        let rect = response.rect;
+        let center = Pos2::new(rect.center().x, rect.bottom() - 10.0);
+        let radius = (rect.width() / 2.0).min(rect.height() - 20.0);
+        let max = self.scale.max();
+
+        // angle runs from PI (left, clean air) to 0 (right, hazardous)
+        let angle_of = |value: f64| PI * (1.0 - (value / max).clamp(0.0, 1.0) as f32);
+        let point_at = |angle: f32, r: f32| center + egui::vec2(r * angle.cos(), -r * angle.sin());
+
+        let segments = 100;
+        for i in 0..segments {
+            let v_start = max * f64::from(i) / f64::from(segments);
+            let v_end = max * f64::from(i + 1) / f64::from(segments);
+            let color = aqi_color(self.scale, self.scale.level(v_end));
+            painter.line_segment(
+                [
+                    point_at(angle_of(v_start), radius),
+                    point_at(angle_of(v_end), radius),
+                ],
+                Stroke::new(10.0, color),
+            );
+        }
+
+        let Some(aqi) = current.aqi(self.scale) else {
+            return;
+        };
+
+        let needle = point_at(angle_of(aqi.value), radius - 14.0);
+        painter.line_segment(
+            [center, needle],
+            Stroke::new(3.0, ui.visuals().text_color()),
+        );
+        painter.circle_filled(center, 5.0, ui.visuals().text_color());
+
+        painter.text(
+            center - egui::vec2(0.0, radius * 0.45),
+            Align2::CENTER_CENTER,
+            format!("{:.0}", aqi.value),
+            FontId::proportional(28.0),
+            aqi_color(self.scale, aqi.level),
+        );
+        painter.text(
+            center - egui::vec2(0.0, radius * 0.2),
+            Align2::CENTER_CENTER,
+            format!("{} ({})", aqi.category(), aqi.dominant.label()),
+            FontId::proportional(13.0),
+            ui.visuals().text_color(),
+        );
+    }
+
+    fn pollutant_grid(&self, ui: &mut Ui, current: &AirQualityPoint) {
+        Grid::new("air_quality_grid")
+            .num_columns(3)
+            .spacing([40.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("Pollutant");
+                ui.strong("µg/m³");
+                ui.strong("Index");
+                ui.end_row();
+
+                for pollutant in Pollutant::ALL {
+                    ui.label(pollutant.label());
+                    ui.label(
+                        current
+                            .concentrations
+                            .get(pollutant)
+                            .map_or("--".to_string(), |v| format!("{v:.1}")),
+                    );
+                    match current.sub_index(self.scale, pollutant) {
+                        Some(aqi) => {
+                            ui.colored_label(
+                                aqi_color(self.scale, aqi.level),
+                                format!("{:.0}", aqi.value),
+                            );
+                        }
+                        None => {
+                            ui.label("--");
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    fn hourly_chart(&self, ui: &mut Ui, hourly: &AirQualityBlock) {
+        let points: PlotPoints<'_> = hourly
+            .iter()
+            .filter(|point| in_day(self.selected, point.time))
+            .filter_map(|point| {
+                point
+                    .aqi(self.scale)
+                    .map(|aqi| [point.time as f64, aqi.value])
+            })
+            .collect();
+
+        let line = Line::new(format!("{} AQI", self.scale.name()), points);
+        let tz: Tz = self.timezone.parse().unwrap_or(chrono_tz::UTC);
+
+        Plot::new("air_quality_chart")
+            .height(160.0)
+            .legend(Legend::default().position(Corner::RightTop))
+            .x_axis_formatter({
+                move |x, _range| {
+                    DateTime::from_timestamp(x.value as i64, 0)
+                        .map(|dt| dt.with_timezone(&tz))
+                        .map_or_else(|| "--".into(), |dt| dt.format("%a %H:%M").to_string())
+                }
+            })
+            .label_formatter({
+                move |name, value| {
+                    let time_str = DateTime::from_timestamp(value.x as i64, 0)
+                        .map(|dt| dt.with_timezone(&tz))
+                        .map_or_else(|| "--".into(), |dt| dt.format("%a %H:%M").to_string());
+
+                    format!("{name}: {:.0} at {}", value.y, time_str)
+                }
+            })
+            .show(ui, |plot_ui| {
+                plot_ui.line(line);
+                if let Some(t) = self.forecast_time {
+                    plot_ui.vline(time_cursor(t));
+                }
+            });
+    }
+}
+
+// standard category colours published with each scale
+fn aqi_color(scale: AqiScale, level: usize) -> Color32 {
+    const US: [Color32; 6] = [
+        Color32::from_rgb(0, 228, 0),
+        Color32::from_rgb(255, 255, 0),
+        Color32::from_rgb(255, 126, 0),
+        Color32::from_rgb(255, 0, 0),
+        Color32::from_rgb(143, 63, 151),
+        Color32::from_rgb(126, 0, 35),
+    ];
+    const EU: [Color32; 6] = [
+        Color32::from_rgb(80, 240, 230),
+        Color32::from_rgb(80, 204, 170),
+        Color32::from_rgb(240, 230, 65),
+        Color32::from_rgb(255, 80, 80),
+        Color32::from_rgb(150, 0, 50),
+        Color32::from_rgb(125, 33, 129),
+    ];
+    let colors = match scale {
+        AqiScale::UsEpa => &US,
+        AqiScale::European => &EU,
+    };
+    colors[level.min(colors.len() - 1)]
+}
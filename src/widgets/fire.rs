@@ -0,0 +1,309 @@
+use chrono_tz::Tz;
+use egui::{Color32, Context, Grid, Ui, Window};
+use egui_plot::{Bar, BarChart, Corner, Legend, MarkerShape, Plot, PlotPoint, Points, Text};
+use lib_weather::{AirQualityPoint, AqiScale, Concentrations, Pollutant, WeatherData};
+
+use super::{format_time, in_day, time_cursor, DaySelection, View, Widget};
+
+struct HourlyFire {
+    time: i64,
+    smoke: Option<f64>,
+    fire_index: Option<f64>,
+}
+
+struct DailyMax {
+    day: i64,
+    smoke_max: Option<f64>,
+    smoke_max_time: Option<i64>,
+    fire_index_max: Option<f64>,
+    fire_index_max_time: Option<i64>,
+}
+
+#[derive(Default)]
+pub struct FireWidget {
+    hourly: Vec<HourlyFire>,
+    daily: Vec<DailyMax>,
+    timezone: String,
+    selected: DaySelection,
+    forecast_time: Option<i64>,
+}
+
+impl Widget for FireWidget {
+    fn name(&self) -> &'static str {
+        "fire & smoke"
+    }
+
+    fn hover_text(&self, open: bool) -> String {
+        format!(
+            "Click to {} wildfire smoke and fire weather data.",
+            if open { "hide" } else { "display" }
+        )
+    }
+
+    fn show(&mut self, ctx: &Context, open: &mut bool) {
+        Window::new(self.name())
+            .open(open)
+            .default_size(egui::vec2(512.0, 512.0))
+            .vscroll(true)
+            .show(ctx, |ui| self.ui(ui));
+    }
+
+    fn update_data(&mut self, data: &dyn WeatherData) {
+        self.hourly = data
+            .hourly()
+            .map(|hours| {
+                hours
+                    .iter()
+                    .map(|h| HourlyFire {
+                        time: h.time,
+                        smoke: h.smoke,
+                        fire_index: h.fire_index,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        self.daily = data
+            .daily()
+            .map(|days| {
+                days.iter()
+                    .map(|d| DailyMax {
+                        day: d.time,
+                        smoke_max: d.smoke_max,
+                        smoke_max_time: d.smoke_max_time,
+                        fire_index_max: d.fire_index_max,
+                        fire_index_max_time: d.fire_index_max_time,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        data.time().0.clone_into(&mut self.timezone);
+    }
+
+    fn select_day(&mut self, day: DaySelection) {
+        self.selected = day;
+    }
+
+    fn select_time(&mut self, time: Option<i64>) {
+        self.forecast_time = time;
+    }
+}
+
+impl View for FireWidget {
+    fn ui(&mut self, ui: &mut Ui) {
+        if self.hourly.is_empty() && self.daily.is_empty() {
+            ui.label("No fire weather data available.");
+            return;
+        }
+
+        let tz: Tz = self.timezone.parse().unwrap_or(chrono_tz::UTC);
+
+        ui.strong("Smoke (µg/m³)");
+        self.chart(
+            ui,
+            "smoke_chart",
+            tz,
+            |h| h.smoke,
+            |d| d.smoke_max.zip(d.smoke_max_time),
+            smoke_level,
+        );
+
+        ui.strong("Fire index");
+        self.chart(
+            ui,
+            "fire_index_chart",
+            tz,
+            |h| h.fire_index,
+            |d| d.fire_index_max.zip(d.fire_index_max_time),
+            fire_level,
+        );
+
+        ui.separator();
+        self.daily_grid(ui, tz);
+    }
+}
+
+impl FireWidget {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    fn chart(
+        &self,
+        ui: &mut Ui,
+        id: &str,
+        tz: Tz,
+        value: impl Fn(&HourlyFire) -> Option<f64>,
+        daily_max: impl Fn(&DailyMax) -> Option<(f64, i64)>,
+        level: fn(f64) -> RiskLevel,
+    ) {
+        let bars: Vec<Bar> = self
+            .hourly
+            .iter()
+            .filter(|h| in_day(self.selected, h.time))
+            .filter_map(|h| {
+                value(h).map(|v| {
+                    Bar::new(h.time as f64, v)
+                        .width(3000.0) // a little under an hour wide
+                        .fill(level(v).color())
+                })
+            })
+            .collect();
+
+        let maxima: Vec<(f64, i64)> = self
+            .daily
+            .iter()
+            .filter(|d| in_day(self.selected, d.day))
+            .filter_map(daily_max)
+            .collect();
+
+        let marker_color = ui.visuals().strong_text_color();
+        Plot::new(id)
+            .height(140.0)
+            .link_axis("fire_axes", [true, false])
+            .legend(Legend::default().position(Corner::RightTop))
+            .x_axis_formatter(move |x, _range| format_time(x.value as i64, tz, "%a %H:%M"))
+            .label_formatter(move |name, value| {
+                let time_str = format_time(value.x as i64, tz, "%a %H:%M");
+                if name.is_empty() {
+                    format!("{:.1} at {time_str}", value.y)
+                } else {
+                    format!("{name}: {:.1} at {time_str}", value.y)
+                }
+            })
+            .show(ui, |plot_ui| {
+                plot_ui.bar_chart(BarChart::new("Hourly", bars));
+                if let Some(t) = self.forecast_time {
+                    plot_ui.vline(time_cursor(t));
+                }
+
+                let points: Vec<[f64; 2]> = maxima.iter().map(|&(v, t)| [t as f64, v]).collect();
+                plot_ui.points(
+                    Points::new("Daily max", points)
+                        .shape(MarkerShape::Diamond)
+                        .radius(5.0)
+                        .color(marker_color),
+                );
+                for (v, t) in maxima {
+                    plot_ui.text(
+                        Text::new(
+                            "",
+                            PlotPoint::new(t as f64, v),
+                            format!("{v:.0} @ {}", format_time(t, tz, "%H:%M")),
+                        )
+                        .anchor(egui::Align2::CENTER_BOTTOM),
+                    );
+                }
+            });
+    }
+
+    fn daily_grid(&self, ui: &mut Ui, tz: Tz) {
+        Grid::new("fire_daily_grid")
+            .num_columns(3)
+            .spacing([40.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("Day");
+                ui.strong("Max smoke");
+                ui.strong("Max fire index");
+                ui.end_row();
+
+                for day in self.daily.iter().filter(|d| in_day(self.selected, d.day)) {
+                    ui.label(format_time(day.day, tz, "%a %d %b"));
+                    max_label(ui, day.smoke_max, day.smoke_max_time, tz, smoke_level);
+                    max_label(
+                        ui,
+                        day.fire_index_max,
+                        day.fire_index_max_time,
+                        tz,
+                        fire_level,
+                    );
+                    ui.end_row();
+                }
+            });
+    }
+}
+
+fn max_label(
+    ui: &mut Ui,
+    value: Option<f64>,
+    time: Option<i64>,
+    tz: Tz,
+    level: fn(f64) -> RiskLevel,
+) {
+    match value {
+        Some(v) => {
+            let risk = level(v);
+            let at = time.map_or(String::new(), |t| {
+                format!(" at {}", format_time(t, tz, "%H:%M"))
+            });
+            ui.colored_label(risk.color(), format!("{v:.1} ({}){at}", risk.label()));
+        }
+        None => {
+            ui.label("--");
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum RiskLevel {
+    Low,
+    Moderate,
+    High,
+    VeryHigh,
+    Extreme,
+}
+
+impl RiskLevel {
+    fn label(self) -> &'static str {
+        match self {
+            RiskLevel::Low => "low",
+            RiskLevel::Moderate => "moderate",
+            RiskLevel::High => "high",
+            RiskLevel::VeryHigh => "very high",
+            RiskLevel::Extreme => "extreme",
+        }
+    }
+
+    fn color(self) -> Color32 {
+        match self {
+            RiskLevel::Low => Color32::from_rgb(0, 228, 0),
+            RiskLevel::Moderate => Color32::from_rgb(255, 255, 0),
+            RiskLevel::High => Color32::from_rgb(255, 126, 0),
+            RiskLevel::VeryHigh => Color32::from_rgb(255, 0, 0),
+            RiskLevel::Extreme => Color32::from_rgb(143, 63, 151),
+        }
+    }
+}
+
+// Fosberg fire weather index, roughly 0-100, where values above 50 are
+// considered significant fire weather.
+fn fire_level(index: f64) -> RiskLevel {
+    match index {
+        i if i < 20.0 => RiskLevel::Low,
+        i if i < 40.0 => RiskLevel::Moderate,
+        i if i < 50.0 => RiskLevel::High,
+        i if i < 75.0 => RiskLevel::VeryHigh,
+        _ => RiskLevel::Extreme,
+    }
+}
+
+// Smoke is a near-surface PM2.5 concentration, so bucket it by the
+// US EPA PM2.5 categories.
+fn smoke_level(smoke: f64) -> RiskLevel {
+    let point = AirQualityPoint {
+        time: 0,
+        concentrations: Concentrations {
+            pm2_5: Some(smoke),
+            ..Default::default()
+        },
+    };
+    match point
+        .sub_index(AqiScale::UsEpa, Pollutant::Pm2_5)
+        .map_or(0, |aqi| aqi.level)
+    {
+        0 => RiskLevel::Low,
+        1 => RiskLevel::Moderate,
+        2 => RiskLevel::High,
+        3 => RiskLevel::VeryHigh,
+        _ => RiskLevel::Extreme,
+    }
+}
@@ -0,0 +1,154 @@
+use egui::{Context, Frame, RichText, Sense, Ui, Window};
+use lib_weather::{Condition, Units, WeatherData};
+
+use super::icons::condition_icon;
+use super::{DaySelection, View, Widget};
+use crate::colormap::TemperatureUnit;
+use crate::model::{timezone, DayForecast};
+use crate::theme::Theme;
+
+// width of each day's column
+const COLUMN_WIDTH: f32 = 96.0;
+
+#[derive(Default)]
+pub struct ForecastWidget {
+    daily: Option<Vec<DayForecast>>,
+    outlook: Option<(String, Condition)>,
+    selected: DaySelection,
+    // a selection made since the other widgets were last told
+    pending: Option<DaySelection>,
+}
+
+impl Widget for ForecastWidget {
+    fn name(&self) -> &'static str {
+        "daily forecast"
+    }
+
+    fn hover_text(&self, open: bool) -> String {
+        format!(
+            "Click to {} the forecast for the coming days.",
+            if open { "hide" } else { "display" }
+        )
+    }
+
+    fn show(&mut self, ctx: &Context, open: &mut bool) {
+        Window::new(self.name())
+            .open(open)
+            .default_size(egui::vec2(720.0, 280.0))
+            .hscroll(true)
+            .vscroll(false)
+            .show(ctx, |ui| self.ui(ui));
+    }
+
+    fn update_data(&mut self, data: &dyn WeatherData) {
+        let tz = timezone(data.time().0);
+        self.daily = data
+            .daily()
+            .map(|daily| DayForecast::all(daily, tz, Units::Us));
+        self.outlook = data
+            .outlook()
+            .map(|(summary, condition)| (summary.to_owned(), condition));
+
+        // a selected day that's no longer in the forecast can't be shown
+        if let Some((start, _)) = self.selected {
+            let still_there = self.daily.iter().flatten().any(|day| day.bounds.0 == start);
+            if !still_there {
+                self.selected = None;
+                self.pending = Some(None);
+            }
+        }
+    }
+
+    fn take_selection(&mut self) -> Option<DaySelection> {
+        self.pending.take()
+    }
+
+    fn select_day(&mut self, day: DaySelection) {
+        self.selected = day;
+    }
+}
+
+impl View for ForecastWidget {
+    fn ui(&mut self, ui: &mut Ui) {
+        let Some(daily) = self.daily.take() else {
+            ui.label("No forecast data available.");
+            return;
+        };
+
+        if let Some((summary, condition)) = &self.outlook {
+            ui.horizontal(|ui| {
+                condition_icon(ui, *condition, 24.0);
+                ui.label(summary);
+            });
+        }
+
+        ui.horizontal(|ui| {
+            for day in &daily {
+                self.day_column(ui, day);
+            }
+        });
+
+        ui.weak(if self.selected.is_some() {
+            "Click the selected day again to show every day."
+        } else {
+            "Click a day to show its hours in the other widgets."
+        });
+
+        self.daily = Some(daily);
+    }
+}
+
+impl ForecastWidget {
+    fn day_column(&mut self, ui: &mut Ui, day: &DayForecast) {
+        let bounds = day.bounds;
+        let is_selected = self.selected == Some(bounds);
+
+        let mut frame = Frame::group(ui.style());
+        if is_selected {
+            frame = frame.fill(ui.visuals().selection.bg_fill);
+        }
+
+        let response = frame
+            .show(ui, |ui| {
+                ui.set_width(COLUMN_WIDTH);
+                ui.vertical_centered(|ui| {
+                    ui.strong(&day.date);
+                    condition_icon(ui, day.condition, 40.0);
+                    if let Some(summary) = &day.summary {
+                        ui.small(summary);
+                    }
+
+                    ui.horizontal(|ui| {
+                        temperature_label(ui, day.high, true);
+                        ui.label("/");
+                        temperature_label(ui, day.low, false);
+                    });
+
+                    ui.label(&day.precipitation);
+                    ui.label(&day.wind);
+                    ui.label(&day.uv);
+                });
+            })
+            .response
+            .interact(Sense::click())
+            .on_hover_cursor(egui::CursorIcon::PointingHand);
+
+        if response.clicked() {
+            self.selected = if is_selected { None } else { Some(bounds) };
+            self.pending = Some(self.selected);
+        }
+    }
+}
+
+fn temperature_label(ui: &mut Ui, temperature: Option<f64>, strong: bool) {
+    let Some(t) = temperature else {
+        ui.label("--");
+        return;
+    };
+    let mut text = RichText::new(format!("{t:.0}°"))
+        .color(Theme::current(ui.ctx()).temperature_color(t, TemperatureUnit::Fahrenheit));
+    if strong {
+        text = text.strong();
+    }
+    ui.label(text);
+}
@@ -0,0 +1,180 @@
+use std::f32::consts::PI;
+
+use egui::{Color32, Painter, Pos2, Rect, Response, Sense, Shape, Stroke, Ui, Vec2};
+use lib_weather::Condition;
+
+const SUN: Color32 = Color32::from_rgb(255, 200, 40);
+const MOON: Color32 = Color32::from_rgb(235, 235, 210);
+const CLOUD: Color32 = Color32::from_rgb(215, 220, 230);
+const BACK_CLOUD: Color32 = Color32::from_rgb(150, 155, 170);
+const STORM_CLOUD: Color32 = Color32::from_rgb(110, 115, 130);
+const RAIN: Color32 = Color32::from_rgb(80, 160, 255);
+const ICE: Color32 = Color32::WHITE;
+const MIST: Color32 = Color32::from_rgb(180, 185, 195);
+
+/// Allocate a `size` square and draw the condition in it.
+pub(super) fn condition_icon(ui: &mut Ui, condition: Condition, size: f32) -> Response {
+    let (response, painter) = ui.allocate_painter(Vec2::splat(size), Sense::hover());
+    paint_condition(&painter, response.rect, condition);
+    response.on_hover_text(condition.label())
+}
+
+/// Draw the condition centred in `rect`, scaled to fit.
+pub(super) fn paint_condition(painter: &Painter, rect: Rect, condition: Condition) {
+    let s = rect.width().min(rect.height());
+    let c = rect.center();
+    let at = |x: f32, y: f32| c + Vec2::new(x, y) * s;
+
+    match condition {
+        Condition::ClearDay => sun(painter, c, s * 0.5),
+        Condition::ClearNight => moon(painter, c, s * 0.36),
+        Condition::PartlyCloudyDay => {
+            sun(painter, at(-0.15, -0.15), s * 0.32);
+            cloud(painter, at(0.08, 0.1), s * 0.7, CLOUD);
+        }
+        Condition::PartlyCloudyNight => {
+            moon(painter, at(-0.12, -0.14), s * 0.24);
+            cloud(painter, at(0.08, 0.1), s * 0.7, CLOUD);
+        }
+        Condition::Cloudy => {
+            cloud(painter, at(0.12, -0.1), s * 0.6, BACK_CLOUD);
+            cloud(painter, at(-0.05, 0.08), s * 0.75, CLOUD);
+        }
+        Condition::Fog => {
+            cloud(painter, at(0.0, -0.15), s * 0.7, BACK_CLOUD);
+            for (y, half) in [(0.12, 0.38), (0.25, 0.3), (0.38, 0.38)] {
+                painter.line_segment([at(-half, y), at(half, y)], Stroke::new(s * 0.05, MIST));
+            }
+        }
+        Condition::Wind => wind(painter, c, s),
+        Condition::Rain => {
+            cloud(painter, at(0.0, -0.12), s * 0.8, CLOUD);
+            for x in [-0.2, 0.0, 0.2] {
+                raindrop(painter, at(x, 0.3), s);
+            }
+        }
+        Condition::Sleet => {
+            cloud(painter, at(0.0, -0.12), s * 0.8, CLOUD);
+            raindrop(painter, at(-0.15, 0.3), s);
+            flake(painter, at(0.15, 0.32), s * 0.09);
+        }
+        Condition::Snow => {
+            cloud(painter, at(0.0, -0.12), s * 0.8, CLOUD);
+            for x in [-0.22, 0.0, 0.22] {
+                flake(painter, at(x, 0.32), s * 0.09);
+            }
+        }
+        Condition::Hail => {
+            cloud(painter, at(0.0, -0.12), s * 0.8, STORM_CLOUD);
+            for (x, y) in [(-0.2, 0.28), (0.0, 0.38), (0.2, 0.28)] {
+                painter.circle_filled(at(x, y), s * 0.05, ICE);
+            }
+        }
+        Condition::Thunderstorm => {
+            cloud(painter, at(0.0, -0.14), s * 0.8, STORM_CLOUD);
+            bolt(painter, at(0.0, 0.04), s);
+            raindrop(painter, at(-0.24, 0.28), s);
+            raindrop(painter, at(0.24, 0.28), s);
+        }
+        Condition::Unknown => {
+            painter.text(
+                c,
+                egui::Align2::CENTER_CENTER,
+                "?",
+                egui::FontId::proportional(s * 0.6),
+                MIST,
+            );
+        }
+    }
+}
+
+fn sun(painter: &Painter, center: Pos2, size: f32) {
+    let r = size * 0.5;
+    painter.circle_filled(center, r * 0.55, SUN);
+    for i in 0..8u8 {
+        let angle = f32::from(i) * PI / 4.0;
+        let dir = Vec2::angled(angle);
+        painter.line_segment(
+            [center + dir * r * 0.72, center + dir * r],
+            Stroke::new(size * 0.06, SUN),
+        );
+    }
+}
+
+// A crescent lit on the left, filled a scanline at a time since it isn't convex.
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+fn moon(painter: &Painter, center: Pos2, r: f32) {
+    // the shadowing disc, shifted right
+    let offset = r * 0.5;
+    let inner = r * 0.85;
+    let rows = (r * 2.0).ceil() as i32;
+    for row in 0..rows {
+        let y = -r + row as f32 + 0.5;
+        let half = (r * r - y * y).max(0.0).sqrt();
+        let left = center.x - half;
+        let mut right = center.x + half;
+        if y.abs() < inner {
+            right = right.min(center.x + offset - (inner * inner - y * y).sqrt());
+        }
+        if right > left {
+            painter.line_segment(
+                [
+                    Pos2::new(left, center.y + y),
+                    Pos2::new(right, center.y + y),
+                ],
+                Stroke::new(1.0, MOON),
+            );
+        }
+    }
+}
+
+// A cloud `width` wide, centred on `center`.
+fn cloud(painter: &Painter, center: Pos2, width: f32, color: Color32) {
+    let w = width;
+    let at = |x: f32, y: f32| center + Vec2::new(x, y) * w;
+    painter.circle_filled(at(-0.26, 0.06), w * 0.19, color);
+    painter.circle_filled(at(0.0, -0.06), w * 0.27, color);
+    painter.circle_filled(at(0.26, 0.06), w * 0.19, color);
+    painter.rect_filled(
+        Rect::from_min_max(at(-0.26, 0.0), at(0.26, 0.25)),
+        0.0,
+        color,
+    );
+}
+
+fn raindrop(painter: &Painter, top: Pos2, s: f32) {
+    painter.line_segment(
+        [top, top + Vec2::new(-0.05, 0.14) * s],
+        Stroke::new(s * 0.05, RAIN),
+    );
+}
+
+fn flake(painter: &Painter, center: Pos2, r: f32) {
+    for i in 0..3u8 {
+        let dir = Vec2::angled(f32::from(i) * PI / 3.0) * r;
+        painter.line_segment([center - dir, center + dir], Stroke::new(r * 0.35, ICE));
+    }
+}
+
+fn bolt(painter: &Painter, top: Pos2, s: f32) {
+    let points = [(0.06, 0.0), (-0.06, 0.16), (0.04, 0.16), (-0.06, 0.36)]
+        .map(|(x, y)| top + Vec2::new(x, y) * s);
+    painter.add(Shape::line(points.to_vec(), Stroke::new(s * 0.06, SUN)));
+}
+
+fn wind(painter: &Painter, center: Pos2, s: f32) {
+    let stroke = Stroke::new(s * 0.06, MIST);
+    for (y, length) in [(-0.2, 0.6), (0.0, 0.75), (0.2, 0.5)] {
+        let start = center + Vec2::new(-0.38, y) * s;
+        let end = start + Vec2::new(length, 0.0) * s;
+        painter.line_segment([start, end], stroke);
+        // curl the end of each gust back on itself
+        let curl: Vec<Pos2> = (0..=6u8)
+            .map(|i| {
+                let angle = PI / 2.0 - f32::from(i) * PI / 4.0;
+                end + Vec2::new(0.0, -0.06 * s) + Vec2::angled(angle) * 0.06 * s
+            })
+            .collect();
+        painter.add(Shape::line(curl, stroke));
+    }
+}
@@ -1,9 +1,20 @@
 use std::collections::BTreeSet;
 
-use egui::{Context, Ui};
-use lib_weather::WeatherData;
+use chrono_tz::Tz;
+use egui::{Color32, Context, Id, LayerId, Order, Slider, Ui};
+use egui_plot::{LineStyle, VLine};
+use lib_weather::{AirQualityData, WeatherData};
+use tracing::{error, warn};
 
+use crate::export::{self, ExportFormat};
+use crate::model::{compass_point, format_time, in_day, DaySelection};
+
+mod air_quality;
 mod current;
+mod fire;
+mod forecast;
+mod icons;
+mod storm;
 mod sun_moon;
 mod temperature;
 
@@ -29,11 +40,52 @@ trait Widget {
     /// Take what the widget needs from the latest `WeatherData`,
     /// run any transformations once, and cache it in the widget's state.
     fn update_data(&mut self, data: &dyn WeatherData);
+
+    /// Same as `update_data`, for a past day shown during playback. Widgets that
+    /// build up state across fetches should leave it as it is.
+    fn update_history(&mut self, data: &dyn WeatherData) {
+        self.update_data(data);
+    }
+
+    /// Same as `update_data`, for the latest `AirQualityData`.
+    fn update_air_quality(&mut self, _data: &dyn AirQualityData) {}
+
+    /// Called with the location being fetched, before any data for it arrives,
+    /// so widgets that can work from the location alone don't need a provider.
+    fn update_location(&mut self, _lat: f64, _lon: f64) {}
+
+    /// A day the user picked in this widget since the last call, to pass on to the others.
+    fn take_selection(&mut self) -> Option<DaySelection> {
+        None
+    }
+
+    /// Focus on a single day picked in another widget.
+    fn select_day(&mut self, _day: DaySelection) {}
+
+    /// Show the conditions at a forecast hour rather than now, or now again when `None`.
+    fn select_time(&mut self, _time: Option<i64>) {}
+}
+
+/// What an export was asked for.
+#[derive(Clone, Copy)]
+enum ExportTarget {
+    Widget(&'static str),
+    Dashboard,
 }
 
 #[derive(Default)]
+#[allow(clippy::struct_field_names)]
 pub struct Widgets {
     widgets: Vec<Box<dyn Widget>>,
+    // hours in the latest hourly forecast, for the timeline
+    hours: Vec<i64>,
+    timezone: String,
+    // hour picked on the timeline, or `None` for now
+    forecast_time: Option<i64>,
+    // export asked for, captured once the widgets have been drawn
+    export: Option<(ExportTarget, ExportFormat)>,
+    // screenshot waiting to be saved
+    screenshot: Option<export::Screenshot>,
 }
 
 impl Widgets {
@@ -43,8 +95,13 @@ impl Widgets {
             widgets: vec![
                 Box::<temperature::TemperatureWidget>::default(),
                 Box::<current::CurrentWidget>::default(),
+                Box::<forecast::ForecastWidget>::default(),
                 Box::<sun_moon::SunMoon>::default(),
+                Box::<air_quality::AirQualityWidget>::default(),
+                Box::<fire::FireWidget>::default(),
+                Box::<storm::StormWidget>::default(),
             ],
+            ..Default::default()
         }
     }
 
@@ -53,6 +110,12 @@ impl Widgets {
             if widget.is_enabled(ui.ctx()) {
                 let mut is_open = open.contains(widget.name());
                 let value = ui.toggle_value(&mut is_open, widget.name());
+                value.context_menu(|ui| {
+                    if let Some(format) = export::buttons(ui) {
+                        self.export = Some((ExportTarget::Widget(widget.name()), format));
+                        ui.close();
+                    }
+                });
                 if tooltips_enabled {
                     value.on_hover_ui(|ui| {
                         ui.label(widget.hover_text(is_open));
@@ -64,10 +127,85 @@ impl Widgets {
     }
 
     pub fn windows(&mut self, ctx: &Context, open: &mut BTreeSet<String>) {
+        let mut selection = None;
         for widget in self.widgets.as_mut_slice() {
             let mut is_open = open.contains(widget.name());
             widget.show(ctx, &mut is_open);
             set_open(open, widget.name(), is_open);
+
+            if let Some(day) = widget.take_selection() {
+                selection = Some(day);
+            }
+        }
+
+        if let Some(day) = selection {
+            for widget in self.widgets.as_mut_slice() {
+                widget.select_day(day);
+            }
+        }
+
+        self.capture(ctx, open);
+    }
+
+    /// PNG and SVG buttons exporting every open widget at once.
+    pub fn export_dashboard(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Export:");
+            if let Some(format) = export::buttons(ui) {
+                self.export = Some((ExportTarget::Dashboard, format));
+            }
+        });
+    }
+
+    // Export what was asked for from the shapes drawn this frame, or from a
+    // screenshot that has since arrived.
+    fn capture(&mut self, ctx: &Context, open: &BTreeSet<String>) {
+        if self.screenshot.as_ref().is_some_and(|s| s.poll(ctx)) {
+            self.screenshot = None;
+        }
+
+        let Some((target, format)) = self.export.take() else {
+            return;
+        };
+        let (name, rect, layers, background) = match target {
+            ExportTarget::Widget(name) => {
+                let id = Id::new(name);
+                let rect = ctx.memory(|m| m.area_rect(id));
+                let Some(rect) = rect.filter(|_| open.contains(name)) else {
+                    warn!("Open the {name} widget to export it");
+                    return;
+                };
+                let layers = vec![LayerId::new(Order::Middle, id)];
+                (name, rect, layers, ctx.style().visuals.window_fill)
+            }
+            ExportTarget::Dashboard => {
+                let mut layers: Vec<LayerId> = ctx.memory(|m| m.layer_ids().collect());
+                if !layers.contains(&LayerId::background()) {
+                    layers.insert(0, LayerId::background());
+                }
+                let background = ctx.style().visuals.panel_fill;
+                ("dashboard", ctx.screen_rect(), layers, background)
+            }
+        };
+        let file_name = export::file_name(name, format);
+
+        match format {
+            ExportFormat::Png => {
+                self.screenshot = Some(export::Screenshot::request(ctx, rect, file_name));
+            }
+            ExportFormat::Svg => {
+                let shapes: Vec<_> = ctx.graphics(|g| {
+                    layers
+                        .iter()
+                        .filter_map(|layer| g.get(*layer))
+                        .flat_map(|list| list.all_entries().cloned())
+                        .collect()
+                });
+                let svg = export::svg(&shapes, rect, background);
+                if let Err(err) = export::save(&file_name, svg.as_bytes()) {
+                    error!("Failed to export {file_name}: {err}");
+                }
+            }
         }
     }
 
@@ -75,7 +213,104 @@ impl Widgets {
         for widget in self.widgets.as_mut_slice() {
             widget.update_data(data);
         }
+        self.update_hours(data);
     }
+
+    /// Same as `update_data`, for a past day shown during playback.
+    pub fn update_history(&mut self, data: &impl WeatherData) {
+        for widget in self.widgets.as_mut_slice() {
+            widget.update_history(data);
+        }
+        self.update_hours(data);
+    }
+
+    fn update_hours(&mut self, data: &impl WeatherData) {
+        self.hours = data
+            .hourly()
+            .map(|hours| hours.iter().map(|h| h.time).collect())
+            .unwrap_or_default();
+        data.time().0.clone_into(&mut self.timezone);
+
+        // stay on the picked hour if the new forecast still covers it
+        let time = self.forecast_time.filter(|t| self.hours.contains(t));
+        self.select_time(time);
+    }
+
+    /// Slider across the hourly forecast, moving every widget to the picked hour.
+    pub fn timeline(&mut self, ui: &mut Ui, tooltips_enabled: bool) {
+        if self.hours.is_empty() {
+            ui.label("No hourly forecast available.");
+            return;
+        }
+
+        let tz: Tz = self.timezone.parse().unwrap_or(chrono_tz::UTC);
+        let mut time = self.forecast_time;
+
+        ui.horizontal(|ui| {
+            // only ever returns to now, a picked hour is left by the slider
+            let now = ui.selectable_label(time.is_none(), "Now");
+            if now.clicked() {
+                time = None;
+            }
+            if tooltips_enabled {
+                now.on_hover_ui(|ui| {
+                    ui.label("Click to show the current conditions.");
+                });
+            }
+
+            let hours = &self.hours;
+            let mut index = time
+                .and_then(|t| hours.iter().position(|&h| h == t))
+                .unwrap_or(0);
+            ui.spacing_mut().slider_width = (ui.available_width() - 120.0).max(100.0);
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let slider = ui.add(
+                Slider::new(&mut index, 0..=hours.len() - 1)
+                    .show_value(true)
+                    .custom_formatter(|i, _| format_time(hours[i as usize], tz, "%a %H:%M")),
+            );
+            if tooltips_enabled {
+                slider.clone().on_hover_ui(|ui| {
+                    ui.label("Drag to show the forecast for a later hour.");
+                });
+            }
+            if slider.changed() {
+                time = Some(hours[index]);
+            }
+        });
+
+        if time != self.forecast_time {
+            self.select_time(time);
+        }
+    }
+
+    /// Move every widget to the given hour, or back to now when `None`.
+    pub fn select_time(&mut self, time: Option<i64>) {
+        self.forecast_time = time;
+        for widget in self.widgets.as_mut_slice() {
+            widget.select_time(time);
+        }
+    }
+
+    pub fn update_air_quality(&mut self, data: &impl AirQualityData) {
+        for widget in self.widgets.as_mut_slice() {
+            widget.update_air_quality(data);
+        }
+    }
+
+    pub fn update_location(&mut self, lat: f64, lon: f64) {
+        for widget in self.widgets.as_mut_slice() {
+            widget.update_location(lat, lon);
+        }
+    }
+}
+
+/// Vertical line marking the forecast hour picked on the timeline.
+#[allow(clippy::cast_precision_loss)]
+fn time_cursor(time: i64) -> VLine {
+    VLine::new("Selected time", time as f64)
+        .color(Color32::WHITE)
+        .style(LineStyle::dashed_loose())
 }
 
 fn set_open(open: &mut BTreeSet<String>, key: &'static str, is_open: bool) {
@@ -0,0 +1,262 @@
+use std::collections::VecDeque;
+
+use egui::{Align2, Color32, Context, FontId, Painter, Pos2, Stroke, Ui, Vec2, Window};
+use lib_weather::WeatherData;
+
+use super::{compass_point, View, Widget};
+
+// number of past fetches to keep for the storm track
+const TRACK_LEN: usize = 12;
+
+// candidate outer ring radii, in miles
+const RANGES: [f64; 8] = [5.0, 10.0, 25.0, 50.0, 100.0, 200.0, 500.0, 1000.0];
+
+/// Position of the nearest storm as seen in one fetch.
+#[derive(Clone, Copy)]
+struct StormFix {
+    time: i64,
+    distance: f64,
+    bearing: Option<f64>,
+}
+
+#[derive(Default)]
+pub struct StormWidget {
+    location: Option<(f64, f64)>,
+    track: VecDeque<StormFix>,
+}
+
+impl Widget for StormWidget {
+    fn name(&self) -> &'static str {
+        "nearest storm"
+    }
+
+    fn hover_text(&self, open: bool) -> String {
+        format!(
+            "Click to {} the distance and bearing of the nearest storm.",
+            if open { "hide" } else { "display" }
+        )
+    }
+
+    fn show(&mut self, ctx: &Context, open: &mut bool) {
+        Window::new(self.name())
+            .open(open)
+            .default_size(egui::vec2(320.0, 360.0))
+            .vscroll(false)
+            .show(ctx, |ui| self.ui(ui));
+    }
+
+    fn update_data(&mut self, data: &dyn WeatherData) {
+        // a track is only meaningful relative to a single location
+        let location = Some(data.location());
+        if self.location != location {
+            self.location = location;
+            self.track.clear();
+        }
+
+        let Some(cur) = data.current() else {
+            return;
+        };
+        let Some(distance) = cur.nearest_storm_distance else {
+            return;
+        };
+
+        let fix = StormFix {
+            time: cur.time,
+            distance,
+            bearing: cur.nearest_storm_bearing,
+        };
+
+        // refetching within the same provider interval yields the same observation
+        if self.track.back().is_some_and(|last| last.time == fix.time) {
+            self.track.pop_back();
+        }
+        self.track.push_back(fix);
+        if self.track.len() > TRACK_LEN {
+            self.track.pop_front();
+        }
+    }
+
+    fn update_history(&mut self, _data: &dyn WeatherData) {
+        // past days would land in the live track out of order, so keep to it
+    }
+}
+
+impl View for StormWidget {
+    fn ui(&mut self, ui: &mut Ui) {
+        let Some(latest) = self.track.back().copied() else {
+            ui.label("No storm data available.");
+            return;
+        };
+
+        let range = RANGES
+            .iter()
+            .copied()
+            .find(|&r| self.track.iter().all(|fix| fix.distance <= r * 0.9))
+            .unwrap_or(RANGES[RANGES.len() - 1]);
+
+        self.draw_radar(ui, range);
+
+        if latest.distance <= 0.0 {
+            ui.label("Storm overhead.");
+        } else {
+            ui.label(format!(
+                "Nearest storm: {:.0} mi{}",
+                latest.distance,
+                latest
+                    .bearing
+                    .map_or(String::new(), |b| format!(" to the {}", compass_point(b)))
+            ));
+        }
+
+        if let Some(trend) = self.trend() {
+            ui.label(trend);
+        }
+    }
+}
+
+impl StormWidget {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    fn draw_radar(&self, ui: &mut Ui, range: f64) {
+        let size = ui.available_width().min(320.0);
+        let (response, painter) = ui.allocate_painter(Vec2::splat(size), egui::Sense::hover());
+
+        let rect = response.rect;
+        let center = rect.center();
+        let radius = size / 2.0 - 16.0;
+        let ring_color = Color32::from_rgb(40, 120, 60);
+        let text_color = ui.visuals().weak_text_color();
+
+        painter.circle_filled(center, radius, Color32::from_rgb(5, 25, 10));
+
+        // range rings at quarters of the outer range
+        for i in 1..=4 {
+            let r = radius * i as f32 / 4.0;
+            painter.circle_stroke(center, r, Stroke::new(1.0, ring_color));
+            painter.text(
+                center + egui::vec2(2.0, -r),
+                Align2::LEFT_BOTTOM,
+                format!("{:.0}", range * f64::from(i) / 4.0),
+                FontId::proportional(10.0),
+                text_color,
+            );
+        }
+
+        // cardinal axes
+        for (label, bearing) in [("N", 0.0), ("E", 90.0), ("S", 180.0), ("W", 270.0)] {
+            let dir = direction(bearing);
+            painter.line_segment(
+                [center, center + dir * radius],
+                Stroke::new(0.5, ring_color),
+            );
+            painter.text(
+                center + dir * (radius + 8.0),
+                Align2::CENTER_CENTER,
+                label,
+                FontId::proportional(12.0),
+                text_color,
+            );
+        }
+
+        // user's location
+        painter.circle_filled(center, 3.0, Color32::WHITE);
+
+        let position = |fix: &StormFix| -> Option<Pos2> {
+            if fix.distance <= 0.0 {
+                return Some(center);
+            }
+            let scale = (fix.distance / range).min(1.0) as f32 * radius;
+            fix.bearing.map(|b| center + direction(b) * scale)
+        };
+
+        let points: Vec<Pos2> = self.track.iter().filter_map(position).collect();
+        let count = points.len();
+
+        // older fixes fade out along the track
+        for (i, pair) in points.windows(2).enumerate() {
+            let alpha = 60 + (195 * (i + 1) / count) as u8;
+            painter.line_segment(
+                [pair[0], pair[1]],
+                Stroke::new(1.5, Color32::from_rgba_unmultiplied(255, 200, 0, alpha)),
+            );
+        }
+        for (i, point) in points.iter().enumerate() {
+            let alpha = 60 + (195 * (i + 1) / count) as u8;
+            let is_latest = i + 1 == count;
+            draw_storm(&painter, *point, is_latest, alpha);
+        }
+    }
+
+    /// Describe how the storm moved between the last two fetches.
+    fn trend(&self) -> Option<String> {
+        let mut recent = self.track.iter().rev();
+        let latest = recent.next()?;
+        let previous = recent.next()?;
+
+        let change = latest.distance - previous.distance;
+        let minutes = (latest.time - previous.time) / 60;
+
+        Some(if change.abs() < 1.0 {
+            format!("Holding steady over the last {minutes} min.")
+        } else if change < 0.0 {
+            format!(
+                "Approaching: {:.0} mi closer over the last {minutes} min.",
+                -change
+            )
+        } else {
+            format!("Retreating: {change:.0} mi further over the last {minutes} min.")
+        })
+    }
+}
+
+fn draw_storm(painter: &Painter, pos: Pos2, is_latest: bool, alpha: u8) {
+    let color = Color32::from_rgba_unmultiplied(255, 60, 60, alpha);
+    if is_latest {
+        painter.circle_filled(pos, 7.0, color);
+        painter.circle_stroke(pos, 11.0, Stroke::new(1.5, color));
+    } else {
+        painter.circle_filled(pos, 3.0, color);
+    }
+}
+
+// unit vector on screen for a compass bearing in degrees (clockwise from north)
+#[allow(clippy::cast_possible_truncation)]
+fn direction(bearing: f64) -> Vec2 {
+    let angle = (bearing as f32).to_radians();
+    egui::vec2(angle.sin(), -angle.cos())
+}
+
+#[cfg(test)]
+mod test {
+    use lib_weather::PirateData;
+    use serde_json::json;
+
+    use super::*;
+
+    fn storm(time: i64, distance: f64) -> PirateData {
+        let currently = json!({
+            "time": time,
+            "nearestStormDistance": distance,
+            "nearestStormBearing": 90.0
+        });
+        PirateData {
+            latitude: 37.233,
+            longitude: -115.8,
+            currently: Some(serde_json::from_value(currently).unwrap()),
+            ..PirateData::default()
+        }
+    }
+
+    #[test]
+    fn playback_leaves_the_track_alone() {
+        let mut widget = StormWidget::default();
+        widget.update_data(&storm(1_000, 40.0));
+        widget.update_data(&storm(2_000, 30.0));
+
+        // a day from last week, shown during playback
+        widget.update_history(&storm(500, 5.0));
+
+        let times: Vec<_> = widget.track.iter().map(|fix| fix.time).collect();
+        assert_eq!(times, [1_000, 2_000]);
+        assert!(widget.trend().unwrap().starts_with("Approaching"));
+    }
+}
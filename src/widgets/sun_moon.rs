@@ -1,16 +1,25 @@
-use std::f32::consts::PI;
+use std::sync::Arc;
 
-use egui::{Color32, Context, Painter, Pos2, Stroke, Ui, Window};
+use egui::{Align2, Color32, Context, FontId, Grid, Painter, Pos2, Rect, Stroke, Ui, Window};
+use lib_weather::WeatherData;
 
 use super::{View, Widget};
-use lib_weather::WeatherData;
+use crate::model::{timezone, Provided, SunMoonModel};
+use crate::theme::Theme;
+
+// locations closer than this, in degrees, are treated as the same place
+const SAME_PLACE: f64 = 0.01;
 
 #[derive(Default)]
 pub struct SunMoon {
-    sunrise: Option<i64>,
-    sunset: Option<i64>,
-    offset: i64,
-    now: i64,
+    timezone: String,
+    location: Option<(f64, f64)>,
+    provided: Provided,
+    // forecast hour to show instead of now
+    forecast_time: Option<i64>,
+    model: Option<SunMoonModel>,
+    // theme the model was built with, or `None` when it needs rebuilding
+    theme: Option<Arc<Theme>>,
 }
 
 impl Widget for SunMoon {
@@ -34,94 +43,211 @@ impl Widget for SunMoon {
     }
 
     fn update_data(&mut self, data: &dyn WeatherData) {
-        let (_, offset) = data.time();
-        self.offset = offset as i64;
+        let (timezone, _) = data.time();
+        timezone.clone_into(&mut self.timezone);
+        self.location = Some(data.location());
+
+        self.provided = Provided::from_data(data);
+        self.theme = None;
+    }
 
-        if let Some(today) = data.daily().and_then(|days| days.first()) {
-            self.sunrise = today.sunrise_time;
-            self.sunset = today.sunset_time;
-            self.now = today.time;
+    fn update_location(&mut self, lat: f64, lon: f64) {
+        let same_place = self.location.is_some_and(|(old_lat, old_lon)| {
+            (old_lat - lat).abs() <= SAME_PLACE && (old_lon - lon).abs() <= SAME_PLACE
+        });
+        if !same_place {
+            // anything the provider sent was for the old location
+            self.timezone.clear();
+            self.provided = Provided::default();
+            self.location = Some((lat, lon));
+            self.theme = None;
         }
     }
+
+    fn select_time(&mut self, time: Option<i64>) {
+        self.forecast_time = time;
+        self.theme = None;
+    }
 }
 
 impl View for SunMoon {
     fn ui(&mut self, ui: &mut Ui) {
-        if let (Some(sunrise), Some(sunset)) = (self.sunrise, self.sunset) {
-            self.draw_sun_arc(ui, sunrise, sunset, self.offset, self.now);
+        let Some(location) = self.location else {
+            ui.label("No location selected.");
+            return;
+        };
+
+        if self.timezone.is_empty() {
+            ui.weak("Times are in UTC until the weather data arrives.");
         }
+
+        let theme = Theme::current(ui.ctx());
+        let now = chrono::Utc::now().timestamp();
+        // a model of today goes stale once the local day is over
+        let day_over = self.forecast_time.is_none()
+            && self
+                .model
+                .as_ref()
+                .is_some_and(|model| now >= model.sun.day_end);
+        if day_over || self.theme.as_ref() != Some(&theme) {
+            let at = self.forecast_time.unwrap_or(now);
+            self.model = Some(SunMoonModel::new(
+                at,
+                location,
+                timezone(&self.timezone),
+                self.provided,
+                &theme,
+            ));
+            self.theme = Some(theme);
+        }
+        let Some(model) = &self.model else {
+            return;
+        };
+        if self.forecast_time.is_none() {
+            let until_tomorrow = u64::try_from(model.sun.day_end - now).unwrap_or(0);
+            ui.ctx()
+                .request_repaint_after(std::time::Duration::from_secs(until_tomorrow));
+        }
+
+        self.draw_sun_path(ui, model);
+        rows_grid(ui, "sun_grid", &model.sun_rows);
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            draw_moon(ui, model.moon.phase, 48.0);
+            rows_grid(ui, "moon_grid", &model.moon.rows);
+        });
     }
 }
+
 impl SunMoon {
-    fn draw_sun_arc(&self, ui: &mut Ui, sunrise: i64, sunset: i64, offset: i64, now: i64) {
+    fn draw_sun_path(&self, ui: &mut Ui, model: &SunMoonModel) {
         let desired_size = egui::vec2(ui.available_width(), 200.0);
         let (response, painter) = ui.allocate_painter(desired_size, egui::Sense::hover());
-
         let rect = response.rect;
-        let center = Pos2::new(rect.center().x, rect.bottom());
-        let radius = rect.width().min(rect.height()) / 2.0;
-
-        let timezone_offset_secs = offset * 3600; // adjust to actual timezone
-
-        let seconds_per_day = 24 * 60 * 60;
-
-        let midnight = (sunrise / seconds_per_day) * seconds_per_day; // anchor to midnight
 
-        // Draw dark background behind the arc
-        painter.rect_filled(rect, 0.0, Color32::from_rgb(10, 10, 30));
-
-        let segments = 100;
-        let t_sunrise =
-            ((sunrise + timezone_offset_secs) % seconds_per_day) as f32 / seconds_per_day as f32;
-        let t_sunset =
-            ((sunset + timezone_offset_secs) % seconds_per_day) as f32 / seconds_per_day as f32;
+        let (bottom, top) = model.altitude_range;
+        let x_at = |t: i64| rect.left() + model.day_fraction(t) * rect.width();
+        #[allow(clippy::cast_possible_truncation)]
+        let y_at = |alt: f64| {
+            let fraction = ((alt - bottom) / (top - bottom)) as f32;
+            rect.bottom() - fraction * rect.height()
+        };
+
+        // horizontal bands for night, the three twilights and day
+        let sky = &Theme::current(ui.ctx()).sky;
+        painter.rect_filled(rect, 0.0, sky.night);
+        for (low, high, color) in [
+            (-18.0, -12.0, sky.astronomical_twilight),
+            (-12.0, -6.0, sky.nautical_twilight),
+            (-6.0, 0.0, sky.civil_twilight),
+            (0.0, top, sky.day),
+        ] {
+            let band = Rect::from_x_y_ranges(rect.x_range(), y_at(high)..=y_at(low));
+            painter.rect_filled(band, 0.0, color);
+        }
 
-        for i in 0..segments {
-            let t_start = i as f32 / segments as f32;
-            let t_end = (i + 1) as f32 / segments as f32;
+        // civil twilight as reported, which may come from the provider
+        let sun = &model.sun;
+        for (from, to) in [(sun.dawn, sun.sunrise), (sun.sunset, sun.dusk)] {
+            if let (Some(from), Some(to)) = (from, to) {
+                let band = Rect::from_x_y_ranges(x_at(from)..=x_at(to), rect.y_range());
+                painter.rect_filled(band, 0.0, sky.twilight_shade);
+            }
+        }
 
-            let angle_start = PI * t_start;
-            let angle_end = PI * t_end;
+        // horizon
+        let horizon = y_at(0.0);
+        painter.line_segment(
+            [
+                Pos2::new(rect.left(), horizon),
+                Pos2::new(rect.right(), horizon),
+            ],
+            Stroke::new(2.0, sky.horizon),
+        );
 
-            let pos_start =
-                center + egui::vec2(radius * angle_start.cos(), -radius * angle_start.sin());
-            let pos_end = center + egui::vec2(radius * angle_end.cos(), -radius * angle_end.sin());
+        for segment in &model.sun_path {
+            let (t0, alt0) = segment.from;
+            let (t1, alt1) = segment.to;
+            painter.line_segment(
+                [
+                    Pos2::new(x_at(t0), y_at(alt0)),
+                    Pos2::new(x_at(t1), y_at(alt1)),
+                ],
+                Stroke::new(3.0, segment.color),
+            );
+        }
 
-            let t = i as f32 / 100.0;
-            let color = sky_color_for_time(t, t_sunrise, t_sunset);
+        // mark sunrise and sunset on the horizon
+        for (t, label) in &model.horizon_marks {
+            let pos = Pos2::new(x_at(*t), horizon);
+            painter.circle_stroke(pos, 5.0, Stroke::new(1.5, sky.sun));
+            painter.text(
+                pos + egui::vec2(0.0, 10.0),
+                Align2::CENTER_TOP,
+                label,
+                FontId::proportional(11.0),
+                sky.labels,
+            );
+        }
 
-            painter.add(egui::Shape::line_segment(
-                [pos_start, pos_end],
-                Stroke::new(4.0, color),
-            ));
+        // the sun's position now, or at the forecast hour being shown
+        let at = self
+            .forecast_time
+            .unwrap_or_else(|| chrono::Utc::now().timestamp());
+        if let Some(altitude) = model.sun_altitude(at) {
+            draw_current_time_dot(&painter, Pos2::new(x_at(at), y_at(altitude)));
         }
+    }
+}
 
-        draw_current_time_dot(&painter, center, radius, now, midnight);
+fn rows_grid(ui: &mut Ui, id: &str, rows: &[(&str, String)]) {
+    Grid::new(id)
+        .num_columns(2)
+        .spacing([40.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            for (label, value) in rows {
+                ui.label(format!("{label}: "));
+                ui.label(value);
+                ui.end_row();
+            }
+        });
+}
 
-        // Draw ground line
+// Draw the moon as seen from the northern hemisphere, lit from the right
+// while waxing and from the left while waning.
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+fn draw_moon(ui: &mut Ui, phase: f64, radius: f32) {
+    let (response, painter) =
+        ui.allocate_painter(egui::Vec2::splat(radius * 2.0 + 4.0), egui::Sense::hover());
+    let center = response.rect.center();
+
+    painter.circle_filled(center, radius, Color32::from_rgb(40, 40, 48));
+
+    let phase = phase.rem_euclid(1.0);
+    let side = if phase < 0.5 { 1.0 } else { -1.0 };
+    // horizontal squash of the terminator ellipse, from 1 (new) through 0 (quarter) to -1 (full)
+    let terminator = (2.0 * std::f64::consts::PI * phase).cos() as f32;
+    let lit = Color32::from_rgb(235, 235, 210);
+
+    // the lit region is not convex for a crescent, so fill it one scanline at a time
+    let rows = (radius * 2.0) as i32;
+    for row in 0..rows {
+        let y = -radius + row as f32 + 0.5;
+        let half_width = (radius * radius - y * y).max(0.0).sqrt();
+        let limb = center.x + side * half_width;
+        let edge = center.x + side * terminator * half_width;
         painter.line_segment(
-            [
-                Pos2::new(center.x - radius, center.y),
-                Pos2::new(center.x + radius, center.y),
-            ],
-            Stroke::new(2.0, Color32::BLACK),
+            [Pos2::new(limb, center.y + y), Pos2::new(edge, center.y + y)],
+            Stroke::new(1.0, lit),
         );
     }
-}
 
-fn sky_color_for_time(time_frac: f32, t_sunrise: f32, t_sunset: f32) -> Color32 {
-    if time_frac >= t_sunrise && time_frac <= t_sunset {
-        Color32::YELLOW // Daylight
-    } else {
-        Color32::BLUE // Night
-    }
+    painter.circle_stroke(center, radius, Stroke::new(1.0, Color32::from_gray(90)));
 }
 
-fn draw_current_time_dot(painter: &Painter, center: Pos2, radius: f32, now: i64, midnight: i64) {
-    let seconds_per_day = 86400.0;
-    let t = ((now - midnight) as f32 / seconds_per_day).clamp(0.0, 1.0);
-    let angle = PI * (1.0 - t); // same flip
-
-    let dot_pos = center + egui::vec2(radius * angle.cos(), -radius * angle.sin());
-    painter.circle_filled(dot_pos, 4.0, Color32::WHITE);
+fn draw_current_time_dot(painter: &Painter, pos: Pos2) {
+    painter.circle_filled(pos, 6.0, Color32::WHITE);
+    painter.circle_stroke(pos, 9.0, Stroke::new(1.0, Color32::WHITE));
 }
@@ -1,10 +1,16 @@
-use chrono::DateTime;
-use chrono_tz::Tz;
-use egui::{Context, Ui, Window};
-use egui_plot::{Corner, Legend, Plot};
+use std::sync::Arc;
+
+use egui::{Context, Rect, Ui, Window};
+use egui_plot::{Corner, Legend, Plot, PlotPoint};
 use lib_weather::{DataBlock, WeatherData};
 
-use super::{View, Widget};
+use super::icons::paint_condition;
+use super::{time_cursor, DaySelection, View, Widget};
+use crate::model::{timezone, TemperatureModel};
+use crate::theme::Theme;
+
+// size of the condition icons along the top of the chart
+const ICON_SIZE: f32 = 24.0;
 
 #[derive(Default)]
 pub struct TemperatureWidget {
@@ -12,6 +18,11 @@ pub struct TemperatureWidget {
     hourly: Option<DataBlock>,
     daily: Option<DataBlock>,
     timezone: String,
+    selected: DaySelection,
+    forecast_time: Option<i64>,
+    model: TemperatureModel,
+    // theme the model was built with, or `None` when it needs rebuilding
+    theme: Option<Arc<Theme>>,
 }
 
 impl Widget for TemperatureWidget {
@@ -39,6 +50,17 @@ impl Widget for TemperatureWidget {
         self.daily = data.daily().cloned();
         let time = data.time();
         time.0.clone_into(&mut self.timezone);
+        self.theme = None;
+    }
+
+    fn select_day(&mut self, day: DaySelection) {
+        self.selected = day;
+        self.theme = None;
+    }
+
+    fn select_time(&mut self, time: Option<i64>) {
+        self.forecast_time = time;
+        self.theme = None;
     }
 }
 
@@ -55,64 +77,69 @@ impl View for TemperatureWidget {
         } else {
             &self.hourly
         };
-
-        if let Some(data) = data {
-            self.temp_bar_chart(ui, data);
-        } else {
+        let Some(data) = data else {
             ui.label("No forecast data available.");
+            return;
+        };
+
+        let theme = Theme::current(ui.ctx());
+        if self.theme.as_ref() != Some(&theme) {
+            self.model = TemperatureModel::new(
+                data,
+                timezone(&self.timezone),
+                self.selected,
+                self.forecast_time,
+                &theme,
+            );
+            self.theme = Some(theme);
         }
+        temp_bar_chart(ui, &self.model);
     }
 }
 
-impl TemperatureWidget {
-    fn temp_bar_chart(&self, ui: &mut Ui, data: &DataBlock) {
-        let bars: Vec<egui_plot::Bar> = data
-            .iter()
-            .map(|point| {
-                let x = point.time as f64;
-                let y = point.temperature.unwrap_or(0.0);
-                egui_plot::Bar::new(x, y)
-                    .width(300.0) // about 5 minutes wide
-                    .fill(temperature_color(y))
-            })
-            .collect();
-
-        let chart = egui_plot::BarChart::new("Temperature", bars);
-        let tz: Tz = self.timezone.parse().unwrap_or(chrono_tz::UTC);
-
-        Plot::new("temp_bar_chart")
-            .legend(Legend::default().position(Corner::RightTop))
-            .x_axis_formatter({
-                move |x, _range| {
-                    DateTime::from_timestamp(x.value as i64, 0)
-                        .map(|dt| dt.with_timezone(&tz))
-                        .map_or_else(|| "--:--".into(), |dt| dt.format("%H:%M").to_string())
-                }
-            })
-            .y_axis_formatter(|y, _| format!("{:.0}°F", y.value))
-            .label_formatter({
-                move |name, value| {
-                    let time_str = DateTime::from_timestamp(value.x as i64, 0)
-                        .map(|dt| dt.with_timezone(&tz))
-                        .map_or_else(|| "--:--".into(), |dt| dt.format("%H:%M").to_string());
-
-                    format!("{name}: {:.1}°F at {}", value.y, time_str)
-                }
-            })
-            .show(ui, |plot_ui| {
-                plot_ui.bar_chart(chart);
-            });
-    }
-}
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+fn temp_bar_chart(ui: &mut Ui, model: &TemperatureModel) {
+    let bars: Vec<egui_plot::Bar> = model
+        .bars
+        .iter()
+        .map(|bar| {
+            egui_plot::Bar::new(bar.time as f64, bar.temperature)
+                .width(300.0) // about 5 minutes wide
+                .fill(bar.color)
+        })
+        .collect();
+    let chart = egui_plot::BarChart::new("Temperature", bars);
+
+    let response = Plot::new("temp_bar_chart")
+        .legend(Legend::default().position(Corner::RightTop))
+        .x_axis_formatter(|x, _range| model.time_label(x.value as i64))
+        .y_axis_formatter(|y, _| format!("{:.0}°F", y.value))
+        .label_formatter(|name, value| model.hover_label(name, value.x as i64, value.y))
+        .show(ui, |plot_ui| {
+            plot_ui.bar_chart(chart);
+            if let Some(t) = model.cursor {
+                plot_ui.vline(time_cursor(t));
+            }
+        });
 
-fn temperature_color(temp: f64) -> egui::Color32 {
-    match temp {
-        t if t < 32.0 => egui::Color32::from_rgb(135, 206, 250), // freezing — light blue
-        t if t < 50.0 => egui::Color32::from_rgb(173, 216, 230), // cold — pale blue
-        t if t < 65.0 => egui::Color32::from_rgb(255, 255, 153), // cool — light yellow
-        t if t < 75.0 => egui::Color32::from_rgb(255, 215, 0),   // pleasant — gold
-        t if t < 85.0 => egui::Color32::from_rgb(255, 165, 0),   // warm — orange
-        t if t < 95.0 => egui::Color32::from_rgb(255, 99, 71),   // hot — tomato
-        _ => egui::Color32::from_rgb(178, 34, 34),               // very hot — firebrick
+    // mark each change in conditions along the top of the chart
+    let frame = *response.transform.frame();
+    let painter = ui.painter_at(frame);
+    let mut last_x = f32::NEG_INFINITY;
+    for &(time, condition) in &model.changes {
+        let x = response
+            .transform
+            .position_from_point(&PlotPoint::new(time as f64, 0.0))
+            .x;
+        // keep icons from overlapping when zoomed out
+        if x - last_x < ICON_SIZE || !frame.x_range().contains(x) {
+            continue;
+        }
+        last_x = x;
+        let rect = Rect::from_center_size(
+            egui::pos2(x, frame.top() + ICON_SIZE / 2.0 + 2.0),
+            egui::Vec2::splat(ICON_SIZE),
+        );
+        paint_condition(&painter, rect, condition);
     }
 }